@@ -0,0 +1,96 @@
+use crate::errors::Error;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Matches text with balanced brackets for options like `{:()}` at the start of `src`, where every
+/// `open` has a matching `close`. With `quoted`, brackets inside of `"..."` or `'...'` are
+/// ignored, where the quotes may contain backslash escapes.
+///
+/// A regex cannot count brackets, so the text is found by hand. `tail` matches the rest of the
+/// format after the text. Like a greedy regex, the longest text is tried first, and shorter ones
+/// are tried as long as `tail` returns [`Error::MatchFailed`]. Returns the text and the result of
+/// `tail`.
+#[doc(hidden)]
+pub fn match_balanced<'a, T>(
+    src: &'a str,
+    open: char,
+    close: char,
+    quoted: bool,
+    mut tail: impl FnMut(&'a str) -> Result<T, Error>,
+) -> Result<(&'a str, T), Error> {
+    for end in balanced_ends(src, open, close, quoted).into_iter().rev() {
+        match tail(&src[end..]) {
+            Err(Error::MatchFailed) => continue,
+            res => return res.map(|value| (&src[..end], value)),
+        }
+    }
+    Err(Error::MatchFailed)
+}
+
+/// Every position in `src` where the text before it is balanced, in ascending order
+fn balanced_ends(src: &str, open: char, close: char, quoted: bool) -> Vec<usize> {
+    let mut ends = vec![0];
+    let mut depth = 0usize;
+    let mut chars = src.chars();
+    while let Some(c) = chars.next() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                // the text can't contain a `close` without an `open`
+                break;
+            }
+            depth -= 1;
+        } else if quoted && (c == '"' || c == '\'') {
+            // skip to the closing quote
+            loop {
+                match chars.next() {
+                    Some(end) if end == c => break,
+                    Some('\\') => {
+                        if chars.next().is_none() {
+                            return ends;
+                        }
+                    }
+                    Some(_) => {}
+                    None => return ends,
+                }
+            }
+        }
+        if depth == 0 {
+            ends.push(src.len() - chars.as_str().len());
+        }
+    }
+    ends
+}
+
+#[test]
+fn balanced() {
+    assert_eq!(balanced_ends("", '(', ')', false), [0]);
+    assert_eq!(balanced_ends("a(b)c", '(', ')', false), [0, 1, 4, 5]);
+    assert_eq!(balanced_ends("a)(b", '(', ')', false), [0, 1]);
+    assert_eq!(balanced_ends("((a)", '(', ')', false), [0]);
+    assert_eq!(balanced_ends("ä(ö)", '(', ')', false), [0, 2, 6]);
+
+    let deep = alloc::format!("{}{}", "[".repeat(100), "]".repeat(100));
+    assert_eq!(balanced_ends(&deep, '[', ']', false), [0, 200]);
+
+    assert_eq!(balanced_ends(r#"(")")x"#, '(', ')', true), [0, 5, 6]);
+    assert_eq!(balanced_ends(r#"(")")x"#, '(', ')', false), [0, 3, 4]);
+    assert_eq!(balanced_ends(r#"a"\")"b"#, '(', ')', true), [0, 1, 6, 7]);
+    assert_eq!(balanced_ends(r#"a"("#, '(', ')', true), [0, 1]);
+    assert_eq!(balanced_ends(r#"a"\"#, '(', ')', true), [0, 1]);
+
+    let res = match_balanced("(a) b) c", '(', ')', false, |rest| {
+        rest.strip_prefix(") ").ok_or(Error::MatchFailed)
+    });
+    assert_eq!(res.unwrap(), ("(a) b", "c"));
+    let res = match_balanced("(a) b", '(', ')', false, |rest| match rest {
+        " b" => Ok(()),
+        _ => Err(Error::MatchFailed),
+    });
+    assert_eq!(res.unwrap(), ("(a)", ()));
+    let res = match_balanced("(a", '(', ')', false, |_| Ok(()));
+    assert_eq!(res.unwrap(), ("", ()));
+    let res = match_balanced("(a", '(', ')', false, |_| Err::<(), _>(Error::MatchFailed));
+    assert!(matches!(res, Err(Error::MatchFailed)));
+}
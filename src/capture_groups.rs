@@ -0,0 +1,77 @@
+/// Counts the capture groups in a regex, which are `(...)`, `(?P<name>...)` and `(?<name>...)`.
+///
+/// Escaped parentheses and parentheses in character classes are ignored. The regex is assumed to
+/// be valid, since it is checked when the final regex is built.
+///
+/// This does not parse the regex, so `#` comments in verbose mode (`(?x)`) are not recognized and
+/// parentheses in them are counted. The generated code compares the count with
+/// [`Regex::captures_len`](regex::Regex::captures_len) and returns
+/// [`Error::InvalidRegexRepresentation`](crate::Error::InvalidRegexRepresentation) if it is wrong.
+#[doc(hidden)]
+pub const fn capture_groups(regex: &str) -> usize {
+    let bytes = regex.as_bytes();
+    let mut count = 0;
+    let mut class_depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1, // skip the escaped character
+            b'[' => {
+                class_depth += 1;
+                // a `]` at the start of a class is a literal
+                if i + 1 < bytes.len() && bytes[i + 1] == b'^' {
+                    i += 1;
+                }
+                if i + 1 < bytes.len() && bytes[i + 1] == b']' {
+                    i += 1;
+                }
+            }
+            b']' if class_depth > 0 => class_depth -= 1,
+            b'(' if class_depth == 0 => {
+                if i + 1 >= bytes.len() || bytes[i + 1] != b'?' {
+                    count += 1;
+                } else if i + 2 < bytes.len()
+                    && (bytes[i + 2] == b'P' || bytes[i + 2] == b'<')
+                    && !(i + 3 < bytes.len() && (bytes[i + 3] == b'=' || bytes[i + 3] == b'!'))
+                {
+                    count += 1; // named group
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Number of capture groups in the regex of a type that are not taken by its `FromScanf`
+/// implementation.
+///
+/// Types that take a single capture only use the whole match, so any groups in their regex are
+/// skipped. Other types have to take all of their groups.
+#[doc(hidden)]
+pub const fn extra_captures(num_captures: usize, regex: &str) -> usize {
+    if num_captures == 1 {
+        capture_groups(regex)
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count() {
+        assert_eq!(capture_groups(r"\d+"), 0);
+        assert_eq!(capture_groups(r"(\d+)-(\d+)"), 2);
+        assert_eq!(capture_groups(r"(?:\d+)(?i:a)(?i)b"), 0);
+        assert_eq!(capture_groups(r"(?P<a>x)(?<b>y)"), 2);
+        assert_eq!(capture_groups(r"\(a\)\\(b)"), 1);
+        assert_eq!(capture_groups(r"[(][^)(][]()][^]()]([[:alpha:](])"), 1);
+        assert_eq!(capture_groups(r"((a)|(b(c)))"), 4);
+        assert_eq!(extra_captures(1, "(a)(b)"), 2);
+        assert_eq!(extra_captures(3, "(a)(b)"), 0);
+    }
+}
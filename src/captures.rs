@@ -0,0 +1,268 @@
+/// The text of the capture groups of a match, which is passed to [`FromScanf::from_matches`](crate::FromScanf::from_matches).
+///
+/// This is an iterator over the capture groups in the order of their opening brackets. Every
+/// element is `Some(text)` if the group participated in the match, or `None` for optional groups
+/// like `(a)?` that did not. The first element of a type is always the whole match of that type,
+/// which is never `None`.
+///
+/// The type is independent of the regex engine that is used to match the input, so
+/// implementations of [`FromScanf`](crate::FromScanf) don't depend on a specific version of the
+/// [`regex`] crate.
+///
+/// ```
+/// # use sscanf::*;
+/// struct Pair<'t>(&'t str, &'t str);
+/// impl RegexRepresentation for Pair<'_> {
+///     const REGEX: &'static str = r"(\w+)=(\w+)";
+/// }
+/// impl<'t> FromScanf<'t> for Pair<'t> {
+///     type Err = std::convert::Infallible;
+///     const NUM_CAPTURES: usize = 3;
+///     fn from_matches(src: &mut Captures<'t>) -> Result<Self, Self::Err> {
+///         src.next(); // skip the whole match
+///         let key = src.next().unwrap().unwrap();
+///         let value = src.next().unwrap().unwrap();
+///         Ok(Pair(key, value))
+///     }
+/// }
+/// let pair = sscanf!("a=b", "{Pair}").unwrap();
+/// assert_eq!((pair.0, pair.1), ("a", "b"));
+/// ```
+#[derive(Debug)]
+pub struct Captures<'t> {
+    source: Source<'t>,
+    next: usize,
+    /// the first mismatch between the capture groups and the types: `(type_name, expected, found)`
+    invalid: Option<(&'static str, usize, usize)>,
+    /// set if a converter found that the input doesn't match after all
+    no_match: bool,
+}
+
+#[derive(Debug)]
+enum Source<'t> {
+    Captures(regex::Captures<'t>),
+    /// capture locations from a [`ScanBuffer`](crate::ScanBuffer) and the input they refer to
+    Locations(&'t str, regex::CaptureLocations),
+    /// the same for the regexes of the `lite` feature
+    #[cfg(feature = "lite")]
+    Lite(regex_lite::Captures<'t>),
+    #[cfg(feature = "lite")]
+    LiteLocations(&'t str, regex_lite::CaptureLocations),
+    /// captures of a format with a custom regex that only `fancy-regex` supports
+    #[cfg(feature = "fancy")]
+    Fancy(fancy_regex::Captures<'t>),
+}
+
+impl<'t> Captures<'t> {
+    #[doc(hidden)]
+    pub fn from_regex(captures: regex::Captures<'t>) -> Self {
+        Self {
+            source: Source::Captures(captures),
+            next: 0,
+            invalid: None,
+            no_match: false,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn from_locations(input: &'t str, locations: regex::CaptureLocations) -> Self {
+        Self {
+            source: Source::Locations(input, locations),
+            next: 0,
+            invalid: None,
+            no_match: false,
+        }
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn from_lite(captures: regex_lite::Captures<'t>) -> Self {
+        Self {
+            source: Source::Lite(captures),
+            next: 0,
+            invalid: None,
+            no_match: false,
+        }
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn from_lite_locations(input: &'t str, locations: regex_lite::CaptureLocations) -> Self {
+        Self {
+            source: Source::LiteLocations(input, locations),
+            next: 0,
+            invalid: None,
+            no_match: false,
+        }
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "fancy")]
+    pub fn from_fancy(captures: fancy_regex::Captures<'t>) -> Self {
+        Self {
+            source: Source::Fancy(captures),
+            next: 0,
+            invalid: None,
+            no_match: false,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn into_locations(self) -> Option<regex::CaptureLocations> {
+        match self.source {
+            Source::Locations(_, locations) => Some(locations),
+            _ => None,
+        }
+    }
+
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn into_lite_locations(self) -> Option<regex_lite::CaptureLocations> {
+        match self.source {
+            Source::LiteLocations(_, locations) => Some(locations),
+            _ => None,
+        }
+    }
+
+    /// Returns the `n`th next element like [`Iterator::nth`], but without advancing the iterator.
+    pub fn peek_nth(&self, n: usize) -> Option<Option<&'t str>> {
+        let index = self.next + n;
+        if index >= self.total_len() {
+            return None;
+        }
+        Some(match &self.source {
+            Source::Captures(captures) => captures.get(index).map(|m| m.as_str()),
+            Source::Locations(input, locations) => {
+                let input: &'t str = input;
+                locations.get(index).map(|(start, end)| &input[start..end])
+            }
+            #[cfg(feature = "lite")]
+            Source::Lite(captures) => captures.get(index).map(|m| m.as_str()),
+            #[cfg(feature = "lite")]
+            Source::LiteLocations(input, locations) => {
+                let input: &'t str = input;
+                locations.get(index).map(|(start, end)| &input[start..end])
+            }
+            #[cfg(feature = "fancy")]
+            Source::Fancy(captures) => captures.get(index).map(|m| m.as_str()),
+        })
+    }
+
+    /// Takes the next capture, which has to exist and participate in the match.
+    ///
+    /// Otherwise, an [`InvalidRegexRepresentation`](crate::errors::Error::InvalidRegexRepresentation)
+    /// error is recorded and an empty string is returned.
+    #[doc(hidden)]
+    pub fn next_str(&mut self, type_name: &'static str) -> &'t str {
+        match self.next() {
+            Some(Some(capture)) => capture,
+            Some(None) => {
+                self.invalid(type_name, 1, 0);
+                ""
+            }
+            None => {
+                self.invalid(type_name, self.next + 1, self.total_len());
+                ""
+            }
+        }
+    }
+
+    /// Takes the next capture, which has to exist, but might not participate in the match
+    #[doc(hidden)]
+    pub fn next_optional(&mut self, type_name: &'static str) -> Option<&'t str> {
+        match self.next() {
+            Some(capture) => capture,
+            None => {
+                self.invalid(type_name, self.next + 1, self.total_len());
+                None
+            }
+        }
+    }
+
+    /// Skips the next `n` captures, which have to exist
+    #[doc(hidden)]
+    pub fn skip_captures(&mut self, n: usize, type_name: &'static str) {
+        let total = self.total_len();
+        if self.next + n > total {
+            self.invalid(type_name, self.next + n, total);
+            self.next = total;
+        } else {
+            self.next += n;
+        }
+    }
+
+    /// Records that `type_name` expected `expected` capture groups but found `found`, unless
+    /// another mismatch was recorded before. Returns the recorded error.
+    #[doc(hidden)]
+    pub fn invalid(
+        &mut self,
+        type_name: &'static str,
+        expected: usize,
+        found: usize,
+    ) -> crate::errors::Error {
+        if self.invalid.is_none() {
+            // panics with the `panic_on_invalid_captures` feature
+            crate::errors::Error::invalid_regex_representation(type_name, expected, found);
+            self.invalid = Some((type_name, expected, found));
+        }
+        let (type_name, expected, found) = self.invalid.unwrap();
+        crate::errors::Error::InvalidRegexRepresentation {
+            type_name,
+            expected,
+            found,
+        }
+    }
+
+    /// Records that the input doesn't match the format, for checks that the regex can't express
+    #[doc(hidden)]
+    pub fn no_match(&mut self) {
+        self.no_match = true;
+    }
+
+    /// Returns the first mismatch that was recorded while taking the captures, or
+    /// [`MatchFailed`](crate::errors::Error::MatchFailed) if [`no_match`](Self::no_match) was called
+    #[doc(hidden)]
+    pub fn take_error(&mut self) -> Option<crate::errors::Error> {
+        let invalid = self.invalid.take().map(|(type_name, expected, found)| {
+            crate::errors::Error::InvalidRegexRepresentation {
+                type_name,
+                expected,
+                found,
+            }
+        });
+        let no_match = core::mem::take(&mut self.no_match);
+        invalid.or(if no_match {
+            Some(crate::errors::Error::MatchFailed)
+        } else {
+            None
+        })
+    }
+
+    fn total_len(&self) -> usize {
+        match &self.source {
+            Source::Captures(captures) => captures.len(),
+            Source::Locations(_, locations) => locations.len(),
+            #[cfg(feature = "lite")]
+            Source::Lite(captures) => captures.len(),
+            #[cfg(feature = "lite")]
+            Source::LiteLocations(_, locations) => locations.len(),
+            #[cfg(feature = "fancy")]
+            Source::Fancy(captures) => captures.len(),
+        }
+    }
+}
+
+impl<'t> Iterator for Captures<'t> {
+    type Item = Option<&'t str>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let ret = self.peek_nth(0)?;
+        self.next += 1;
+        Some(ret)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.total_len() - self.next;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Captures<'_> {}
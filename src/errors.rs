@@ -1,26 +1,22 @@
 //! Various error types used by the crate. The most important one is [`Error`], which is returned by [`sscanf`](crate::sscanf).
 
-use std::error; // can't use `Error` directly because of naming conflict; can't alias because that would show up in docs
-use std::fmt::{self, Display};
-use std::str::FromStr;
-
-#[doc(hidden)]
-pub static EXPECT_NEXT_HINT: &str = r#"sscanf: Invalid number of capture groups in regex.
-If you use ( ) in a custom Regex, please add a '?:' at the beginning to avoid forming a capture group like this:
-    "  (  )  "  =>  "  (?:  )  ""#;
-
-#[doc(hidden)]
-pub static EXPECT_CAPTURE_HINT: &str = r#"sscanf: Non-optional capture group marked as optional.
-This is either a problem with a custom regex or RegexRepresentation implementation or an internal error."#;
+use crate::error; // can't use `Error` directly because of naming conflict; can't alias because that would show up in docs
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::fmt::{self, Display};
+use core::str::FromStr;
 
 #[doc(hidden)]
 pub static WRONG_CAPTURES_HINT: &str = r#"
-If you use ( ) in a custom Regex, please add a '?:' at the beginning to avoid forming a capture group like this:
-    "  (  )  "  =>  "  (?:  )  "
+If you implement FromScanf or FromScanfWithOptions manually, make sure that NUM_CAPTURES is the number of capture groups in the regex + 1 for the whole match.
+Parentheses in `#` comments of verbose regexes (`(?x)`) are counted as capture groups and have to be escaped
 "#;
 
 /// The Error returned by [`sscanf`](crate::sscanf).
+///
+/// More variants might be added in the future, so matches on this enum need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// The Regex generated from the format string did not match the input
     MatchFailed,
@@ -34,6 +30,71 @@ pub enum Error {
     /// The exact content of this error is only relevant when debugging custom implementations of
     /// [`FromStr`] or [`FromScanf`](crate::FromScanf).
     ParsingFailed(Box<dyn error::Error>),
+    /// The capture groups of the regex don't match what the types expect
+    ///
+    /// This means that the [`RegexRepresentation`](crate::RegexRepresentation) or the
+    /// [`NUM_CAPTURES`](crate::FromScanf::NUM_CAPTURES) of a manual implementation is wrong, or that
+    /// there is a bug in `sscanf`. Unlike the other variants, this error does not depend on the
+    /// input, so it should be fixed instead of handled.
+    ///
+    /// With the `panic_on_invalid_captures` feature, `sscanf` panics instead of returning this
+    /// error.
+    InvalidRegexRepresentation {
+        /// The type whose capture groups don't match, or the format string if no single type is
+        /// responsible (for display purposes only)
+        type_name: &'static str,
+        /// The number of capture groups that were expected
+        expected: usize,
+        /// The number of capture groups that were found or that participated in the match
+        found: usize,
+    },
+    /// The regex could not be built
+    ///
+    /// This happens if the regex is too large for the `size_limit` of the
+    /// [`RegexOptions`](crate::RegexOptions), or if a custom regex or a
+    /// [`RegexRepresentation`](crate::RegexRepresentation) is invalid in a way that can't be
+    /// checked at compile time. Like [`InvalidRegexRepresentation`](Error::InvalidRegexRepresentation),
+    /// this error does not depend on the input.
+    ///
+    /// With the `fancy` feature, this is also returned if `fancy-regex` fails while matching, like
+    /// when its backtrack limit is exceeded.
+    InvalidRegex {
+        /// The format string, or the type whose regex could not be built (for display purposes
+        /// only)
+        type_name: &'static str,
+        /// The error message of the regex engine
+        message: String,
+    },
+}
+
+impl Error {
+    /// Creates an [`InvalidRegexRepresentation`](Error::InvalidRegexRepresentation) error, or
+    /// panics with the `panic_on_invalid_captures` feature
+    #[doc(hidden)]
+    pub fn invalid_regex_representation(
+        type_name: &'static str,
+        expected: usize,
+        found: usize,
+    ) -> Self {
+        let error = Error::InvalidRegexRepresentation {
+            type_name,
+            expected,
+            found,
+        };
+        if cfg!(feature = "panic_on_invalid_captures") {
+            panic!("{}", error);
+        }
+        error
+    }
+
+    /// Creates an [`InvalidRegex`](Error::InvalidRegex) error
+    #[doc(hidden)]
+    pub fn invalid_regex(type_name: &'static str, error: &impl Display) -> Self {
+        Error::InvalidRegex {
+            type_name,
+            message: error.to_string(),
+        }
+    }
 }
 
 impl error::Error for Error {
@@ -42,6 +103,8 @@ impl error::Error for Error {
         match self {
             Error::MatchFailed => None,
             Error::ParsingFailed(err) => Some(err.as_ref()),
+            Error::InvalidRegexRepresentation { .. } => None,
+            Error::InvalidRegex { .. } => None,
         }
     }
 }
@@ -51,6 +114,23 @@ impl Display for Error {
         match self {
             Error::MatchFailed => write!(f, "sscanf: The input did not match the format string"),
             Error::ParsingFailed(e) => write!(f, "sscanf: Parsing failed: {}", e),
+            Error::InvalidRegexRepresentation {
+                type_name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "sscanf: Invalid capture groups for {}: expected {}, found {}{}",
+                type_name,
+                expected,
+                found,
+                WRONG_CAPTURES_HINT.trim_end()
+            ),
+            Error::InvalidRegex { type_name, message } => write!(
+                f,
+                "sscanf: Cannot build the regex for {}: {}",
+                type_name, message
+            ),
         }
     }
 }
@@ -72,7 +152,7 @@ where
 {
     pub(crate) fn new(error: <T as FromStr>::Err) -> Self {
         Self {
-            type_name: std::any::type_name::<T>(),
+            type_name: core::any::type_name::<T>(),
             error,
         }
     }
@@ -167,6 +247,43 @@ impl Display for MissingPrefixError {
 
 impl error::Error for MissingPrefixError {}
 
+/// Error type used when using the `{:x}` format option on floats if the input is not a valid
+/// hexadecimal float
+#[derive(Debug)]
+pub struct InvalidHexFloatError;
+
+impl Display for InvalidHexFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid hexadecimal float")
+    }
+}
+
+impl error::Error for InvalidHexFloatError {}
+
+/// Error type used when using the `{:q}` format option if the quoted text contains an unknown
+/// escape sequence
+#[derive(Debug)]
+pub struct InvalidEscapeError {
+    /// The escape sequence, including the backslash
+    pub sequence: String,
+}
+
+impl InvalidEscapeError {
+    pub(crate) fn new(sequence: &str) -> Self {
+        Self {
+            sequence: sequence.to_string(),
+        }
+    }
+}
+
+impl Display for InvalidEscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid escape sequence: {}", self.sequence)
+    }
+}
+
+impl error::Error for InvalidEscapeError {}
+
 /// Error type used when a `[sscanf(filter_map = ...)]` closure returns `None`
 #[derive(Debug)]
 pub struct FilterMapNoneError {
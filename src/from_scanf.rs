@@ -1,13 +1,16 @@
-use std::error::Error;
-use std::str::FromStr;
+use alloc::boxed::Box;
+use core::str::FromStr;
+
+use crate::error::Error;
 
 use crate::errors::FromStrFailedError;
+use crate::Captures;
 
 /// A trait that allows you to use a custom regex for parsing a type.
 ///
 /// There are three options to implement this trait:
 /// - `#[derive(FromScanf)]` (recommended)
-/// - implement [`std::str::FromStr`] and relying on the [blanket implementation](#impl-FromScanf<%27t>)
+/// - implement [`std::str::FromStr`](core::str::FromStr) and relying on the [blanket implementation](#impl-FromScanf<%27t>)
 /// - manual implementation (highly discouraged)
 ///
 /// The second and third options also require you to implement [`RegexRepresentation`](crate::RegexRepresentation),
@@ -111,17 +114,17 @@ use crate::errors::FromStrFailedError;
 ///     /// since if the above regex matches, the parsing cannot fail.
 ///     type Err = std::convert::Infallible;
 ///     const NUM_CAPTURES: usize = 4; // 3 capture groups + the whole match
-///     fn from_matches(src: &mut regex::SubCaptureMatches) -> Result<Self, Self::Err> {
+///     fn from_matches(src: &mut sscanf::Captures) -> Result<Self, Self::Err> {
 ///         let _ = src.next().unwrap().unwrap(); // skip the whole match
-///         // note the double-unwrap, since SubCaptureMatches::next() returns an Option<Option<Match>>
+///         // note the double-unwrap, since Captures::next() returns an Option<Option<&str>>
 ///
 ///         // checking the prefix is not necessary here, since the regex already enforces it
 ///
-///         let r_str = src.next().unwrap().unwrap().as_str(); // unwrap is ok because the regex only matches if all capture groups match
+///         let r_str = src.next().unwrap().unwrap(); // unwrap is ok because the regex only matches if all capture groups match
 ///         let r = u8::from_str_radix(r_str, 16).unwrap();
-///         let g_str = src.next().unwrap().unwrap().as_str();
+///         let g_str = src.next().unwrap().unwrap();
 ///         let g = u8::from_str_radix(g_str, 16).unwrap();
-///         let b_str = src.next().unwrap().unwrap().as_str();
+///         let b_str = src.next().unwrap().unwrap();
 ///         let b = u8::from_str_radix(b_str, 16).unwrap();
 ///         // note that every result can be unwrapped here:
 ///         // This is possible because this trait is only used on a match to the RegexRepresentation::REGEX,
@@ -149,8 +152,9 @@ use crate::errors::FromStrFailedError;
 /// The contract is:
 /// - `NUM_CAPTURES` **IS EQUAL TO**
 /// - the number of consumed elements from the iterator passed to [`from_matches`](FromScanf::from_matches) **IS EQUAL TO**
-/// - 1 + the number of unescaped capture groups in [`RegexRepresentation`](crate::RegexRepresentation) (or `{:/.../}`).
-///   The 1 is for the whole match, which is a capture group added by `sscanf`.
+/// - 1 + the number of unescaped capture groups in [`RegexRepresentation`](crate::RegexRepresentation).
+///   The 1 is for the whole match, which is a capture group added by `sscanf`. If `NUM_CAPTURES`
+///   is 1, any capture groups in the regex are skipped by `sscanf` instead.
 ///
 /// All of these are automatically enforced by the derive macro or the [`FromStr`] implementation,
 /// which is why they should be preferred over this option.
@@ -158,7 +162,7 @@ use crate::errors::FromStrFailedError;
 /// #### Lifetime Parameter
 /// The lifetime parameter of `FromScanf` is the borrow from the input string given to `sscanf`.
 /// If your type borrows parts of that string, like `&str` does, you need to specify the lifetime
-/// parameter and match it with the _second_ lifetime parameter of [`regex::SubCaptureMatches`]:
+/// parameter and match it with the lifetime parameter of [`Captures`](crate::Captures):
 /// ```
 /// struct Name<'a, 'b> {
 ///     first: &'a str,
@@ -172,10 +176,10 @@ use crate::errors::FromStrFailedError;
 /// impl<'t> sscanf::FromScanf<'t> for Name<'t, 't> { // both parts are given the same input => same lifetime
 ///     type Err = std::convert::Infallible;
 ///     const NUM_CAPTURES: usize = 3;
-///     fn from_matches(src: &mut regex::SubCaptureMatches<'_, 't>) -> Result<Self, Self::Err> {
+///     fn from_matches(src: &mut sscanf::Captures<'t>) -> Result<Self, Self::Err> {
 ///         let _ = src.next().unwrap().unwrap(); // skip the whole match
-///         let first = src.next().unwrap().unwrap().as_str();
-///         let last = src.next().unwrap().unwrap().as_str();
+///         let first = src.next().unwrap().unwrap();
+///         let last = src.next().unwrap().unwrap();
 ///         Ok(Self { first, last })
 ///     }
 /// }
@@ -216,13 +220,13 @@ where
     /// Number of captures taken by this regex.
     ///
     /// **HAS** to match the number of unescaped capture groups in the [`RegexRepresentation`](crate::RegexRepresentation)
-    /// +1 for the whole match.
+    /// +1 for the whole match, unless it is 1, in which case the capture groups are skipped.
     const NUM_CAPTURES: usize;
 
     /// The implementation of the parsing.
     ///
     /// **HAS** to take **EXACTLY** `NUM_CAPTURES` elements from the iterator.
-    fn from_matches(src: &mut regex::SubCaptureMatches<'_, 't>) -> Result<Self, Self::Err>;
+    fn from_matches(src: &mut Captures<'t>) -> Result<Self, Self::Err>;
 
     /// Convenience shortcut for directly using this trait.
     ///
@@ -248,26 +252,52 @@ where
     where
         Self: crate::RegexRepresentation,
     {
-        let regex = format!("^{}$", Self::REGEX);
-        #[allow(unused_qualifications)] // would complain about the `crate::` prefix, but we
-        // specifically want the bundled regex rather than whatever a user has renamed to `regex`
-        let regex = crate::regex::Regex::new(&regex).unwrap_or_else(|err| {
-            panic!(
-                "sscanf: Type {} has invalid RegexRepresentation `{}`: {}",
-                std::any::type_name::<Self>(),
-                Self::REGEX,
-                err
-            )
-        });
+        let type_name = core::any::type_name::<Self>();
+        let regex = alloc::format!("^{}$", Self::REGEX);
+        let options = Self::REGEX_OPTIONS;
+        let (captures_len, captures) = match () {
+            #[cfg(feature = "lite")]
+            _ if options.lite_supports() => {
+                let regex = options
+                    .build_lite(&regex)
+                    .map_err(|err| crate::errors::Error::invalid_regex(type_name, &err))?;
+                (
+                    regex.captures_len(),
+                    regex.captures(src).map(Captures::from_lite),
+                )
+            }
+            _ => {
+                let regex = options
+                    .build(&regex)
+                    .map_err(|err| crate::errors::Error::invalid_regex(type_name, &err))?;
+                (
+                    regex.captures_len(),
+                    regex.captures(src).map(Captures::from_regex),
+                )
+            }
+        };
+        let expected = Self::NUM_CAPTURES + crate::extra_captures(Self::NUM_CAPTURES, Self::REGEX);
+        if captures_len != expected {
+            return Err(crate::errors::Error::invalid_regex_representation(
+                type_name,
+                expected,
+                captures_len,
+            ));
+        }
 
-        regex
-            .captures(src)
+        captures
             .ok_or_else(|| crate::errors::Error::MatchFailed)
-            .and_then(|cap| {
-                let mut src = cap.iter();
-
-                Self::from_matches(&mut src)
-                    .map_err(|e| crate::errors::Error::ParsingFailed(Box::new(e)))
+            .and_then(|mut src| {
+                let res = Self::from_matches(&mut src)
+                    .map_err(|e| crate::errors::Error::ParsingFailed(Box::new(e)));
+                let taken = captures_len - src.len();
+                if res.is_ok() && taken != Self::NUM_CAPTURES {
+                    src.invalid(type_name, Self::NUM_CAPTURES, taken);
+                }
+                match src.take_error() {
+                    Some(error) => Err(error),
+                    None => res,
+                }
             })
     }
 }
@@ -279,11 +309,8 @@ where
 {
     type Err = FromStrFailedError<T>;
     const NUM_CAPTURES: usize = 1;
-    fn from_matches(src: &mut regex::SubCaptureMatches<'_, 't>) -> Result<Self, Self::Err> {
-        src.next()
-            .expect(crate::errors::EXPECT_NEXT_HINT)
-            .expect(crate::errors::EXPECT_CAPTURE_HINT)
-            .as_str()
+    fn from_matches(src: &mut Captures<'t>) -> Result<Self, Self::Err> {
+        src.next_str(core::any::type_name::<T>())
             .parse()
             .map_err(Self::Err::new)
     }
@@ -0,0 +1,129 @@
+use crate::error::Error;
+
+use crate::Captures;
+
+/// A trait for types that offer their own format options, like `{Date:iso}` or `{Money:eur}`.
+///
+/// Any format option that is not built in is passed to this trait as written. The type lists the
+/// options it understands together with the regex for each of them, and receives the name of the
+/// option again when parsing. This allows one type to support several formats without needing a
+/// newtype for every variant.
+///
+/// The options that only apply to the types from `std`, like `trim`, `alpha` or `.2`, are passed
+/// on as well. The options that work on any type are reserved: custom regexes (`/.../`, `[...]`
+/// and `@path`), the radix options `x`, `o`, `b` and `r` followed by digits, anything that starts
+/// or ends with `#`, `take=N`, balanced brackets (`()`, `[]` and `<>`) and alternatives like
+/// `(a|b)`.
+///
+/// ```
+/// use sscanf::FromScanfWithOptions;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Date {
+///     year: u16,
+///     month: u8,
+///     day: u8,
+/// }
+///
+/// impl<'t> FromScanfWithOptions<'t> for Date {
+///     type Err = std::num::ParseIntError;
+///     const OPTIONS: &'static [(&'static str, &'static str)] = &[
+///         ("iso", r"(\d{4})-(\d{2})-(\d{2})"), // year-month-day
+///         ("us", r"(\d{2})/(\d{2})/(\d{4})"),  // month/day/year
+///     ];
+///     const NUM_CAPTURES: usize = 4; // 3 capture groups + the whole match
+///
+///     fn from_matches_with(
+///         option: &str,
+///         src: &mut sscanf::Captures<'t>,
+///     ) -> Result<Self, Self::Err> {
+///         let _ = src.next().unwrap().unwrap(); // skip the whole match
+///         let mut next = || src.next().unwrap().unwrap();
+///         let (a, b, c) = (next(), next(), next());
+///         let (year, month, day) = match option {
+///             "iso" => (a, b, c),
+///             _ => (c, a, b),
+///         };
+///         Ok(Date {
+///             year: year.parse()?,
+///             month: month.parse()?,
+///             day: day.parse()?,
+///         })
+///     }
+/// }
+///
+/// let input = "released 2024-03-17, patched 04/02/2024";
+/// let (released, patched) = sscanf::sscanf!(input, "released {Date:iso}, patched {Date:us}").unwrap();
+/// assert_eq!(released, Date { year: 2024, month: 3, day: 17 });
+/// assert_eq!(patched, Date { year: 2024, month: 4, day: 2 });
+/// ```
+///
+/// Using an option that is not listed in [`OPTIONS`](FromScanfWithOptions::OPTIONS) is a compile
+/// error. Since panics are not available in constant evaluation on the minimum supported Rust
+/// version, rustc reports it as an "index out of bounds" error inside of `option_regex`, where
+/// the failing line explains that the format option is unknown.
+///
+/// The same contract as for a manual implementation of [`FromScanf`](crate::FromScanf) applies:
+/// The regex of every option has to contain exactly `NUM_CAPTURES - 1` capture groups, and
+/// [`from_matches_with`](FromScanfWithOptions::from_matches_with) has to take exactly
+/// `NUM_CAPTURES` elements from the iterator.
+pub trait FromScanfWithOptions<'t>
+where
+    Self: Sized,
+{
+    /// Error type
+    type Err: Error + 'static;
+
+    /// The supported options as `(name, regex)` pairs.
+    const OPTIONS: &'static [(&'static str, &'static str)];
+
+    /// Number of captures taken by the regex of each option.
+    ///
+    /// **HAS** to match the number of unescaped capture groups in the regex of every option
+    /// +1 for the whole match. Defaults to 1, meaning that the regexes have no capture groups.
+    const NUM_CAPTURES: usize = 1;
+
+    /// The implementation of the parsing. `option` is the name of the option from the format
+    /// string, which is always one of the names in [`OPTIONS`](FromScanfWithOptions::OPTIONS).
+    ///
+    /// **HAS** to take **EXACTLY** `NUM_CAPTURES` elements from the iterator.
+    fn from_matches_with(option: &str, src: &mut Captures<'t>) -> Result<Self, Self::Err>;
+}
+
+/// Finds the regex for `option` in the `OPTIONS` of a [`FromScanfWithOptions`] implementation.
+///
+/// Fails to compile if the option does not exist.
+#[doc(hidden)]
+pub const fn option_regex(
+    options: &'static [(&'static str, &'static str)],
+    option: &str,
+) -> &'static str {
+    let mut i = 0;
+    while i < options.len() {
+        let (name, regex) = options[i];
+        if str_eq(name, option) {
+            return regex;
+        }
+        i += 1;
+    }
+    // panicking in a const fn requires Rust 1.57, so an out of bounds index is used instead.
+    // rustc shows the failing line, so the explanation has to be on the same line
+    let unknown_format_option: [&str; 0] = [];
+    #[allow(unconditional_panic, clippy::out_of_bounds_indexing)]
+    unknown_format_option[0] // ERROR: the format option is not in the `OPTIONS` of the type
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
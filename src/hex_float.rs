@@ -0,0 +1,152 @@
+use crate::errors::InvalidHexFloatError;
+
+/// Conversion from the hexadecimal float notation used by C's `%a` (e.g. `0x1.8p+3`).
+///
+/// Used by the `{:x}` format option on `f32` and `f64`. The conversion is exact, rounding to
+/// the nearest representable value (ties to even) without going through a decimal representation.
+#[doc(hidden)]
+pub trait HexFloat: Sized {
+    /// Parses a hexadecimal float of the form `[+-]?(0x)?<hex digits>[.<hex digits>]p[+-]<decimal exponent>`
+    fn from_hex_str(src: &str) -> Result<Self, InvalidHexFloatError>;
+}
+
+impl HexFloat for f32 {
+    fn from_hex_str(src: &str) -> Result<Self, InvalidHexFloatError> {
+        parse_bits(src, 23, 8).map(|bits| f32::from_bits(bits as u32))
+    }
+}
+impl HexFloat for f64 {
+    fn from_hex_str(src: &str) -> Result<Self, InvalidHexFloatError> {
+        parse_bits(src, 52, 11).map(f64::from_bits)
+    }
+}
+
+/// Parses a hexadecimal float into the bits of an IEEE 754 binary float with `mantissa_bits`
+/// explicitly stored mantissa bits and `exponent_bits` exponent bits.
+fn parse_bits(
+    src: &str,
+    mantissa_bits: u32,
+    exponent_bits: u32,
+) -> Result<u64, InvalidHexFloatError> {
+    let (negative, rest) = match src.as_bytes().first() {
+        Some(b'-') => (true, &src[1..]),
+        Some(b'+') => (false, &src[1..]),
+        _ => (false, src),
+    };
+    let rest = rest
+        .strip_prefix("0x")
+        .or_else(|| rest.strip_prefix("0X"))
+        .unwrap_or(rest);
+
+    let exp_start = rest
+        .find(|c| c == 'p' || c == 'P')
+        .ok_or(InvalidHexFloatError)?;
+    let (digits, exp_text) = (&rest[..exp_start], &rest[exp_start + 1..]);
+
+    // mantissa: value = mantissa * 2^exponent, with `sticky` marking any non-zero bits that did
+    // not fit into `mantissa`
+    let mut mantissa = 0u64;
+    let mut exponent = 0i64;
+    let mut sticky = false;
+    let mut seen_digit = false;
+    let mut seen_point = false;
+    for c in digits.chars() {
+        if c == '.' {
+            if seen_point {
+                return Err(InvalidHexFloatError);
+            }
+            seen_point = true;
+            continue;
+        }
+        let digit = c.to_digit(16).ok_or(InvalidHexFloatError)? as u64;
+        seen_digit = true;
+        if mantissa >> 60 == 0 {
+            mantissa = mantissa << 4 | digit;
+            if seen_point {
+                exponent -= 4;
+            }
+        } else {
+            // no more space => only remember if there was something
+            sticky |= digit != 0;
+            if !seen_point {
+                exponent += 4;
+            }
+        }
+    }
+    if !seen_digit {
+        return Err(InvalidHexFloatError);
+    }
+
+    let (exp_negative, exp_digits) = match exp_text.as_bytes().first() {
+        Some(b'-') => (true, &exp_text[1..]),
+        Some(b'+') => (false, &exp_text[1..]),
+        _ => (false, exp_text),
+    };
+    if exp_digits.is_empty() {
+        return Err(InvalidHexFloatError);
+    }
+    let mut exp_value = 0i64;
+    for c in exp_digits.chars() {
+        let digit = c.to_digit(10).ok_or(InvalidHexFloatError)? as i64;
+        // anything beyond this is out of range for any float type anyway
+        exp_value = (exp_value * 10 + digit).min(1 << 24);
+    }
+    exponent += if exp_negative { -exp_value } else { exp_value };
+
+    let sign_bit = (negative as u64) << (mantissa_bits + exponent_bits);
+
+    if mantissa == 0 {
+        return Ok(sign_bit);
+    }
+
+    // normalize, so that the value is in [2^e, 2^(e+1))
+    let leading_zeros = mantissa.leading_zeros();
+    let mantissa = (mantissa << leading_zeros) as u128;
+    let e = exponent - leading_zeros as i64 + 63;
+
+    let bias = (1i64 << (exponent_bits - 1)) - 1;
+    let min_exp = 1 - bias;
+    let infinity = ((1u64 << exponent_bits) - 1) << mantissa_bits;
+    if e > bias {
+        return Ok(sign_bit | infinity);
+    }
+
+    // number of bits that have to be cut off from the 64-bit mantissa
+    let shift = if e >= min_exp {
+        63 - mantissa_bits as i64
+    } else {
+        63 - mantissa_bits as i64 + (min_exp - e)
+    };
+    if shift >= 128 {
+        // too small even for the smallest subnormal number
+        return Ok(sign_bit);
+    }
+    let shift = shift as u32;
+
+    let mut result = (mantissa >> shift) as u64;
+    let remainder = mantissa & ((1u128 << shift) - 1);
+    let half = 1u128 << (shift - 1);
+    if remainder > half || (remainder == half && (sticky || result & 1 == 1)) {
+        result += 1;
+    }
+
+    let bits = if e >= min_exp {
+        let mut e = e;
+        if result >> (mantissa_bits + 1) != 0 {
+            // rounding overflowed into the next exponent
+            result >>= 1;
+            e += 1;
+            if e > bias {
+                return Ok(sign_bit | infinity);
+            }
+        }
+        let biased = (e + bias) as u64;
+        biased << mantissa_bits | (result & ((1 << mantissa_bits) - 1))
+    } else {
+        // subnormal numbers have a biased exponent of 0. If rounding overflowed into the
+        // implicit bit, the result is the smallest normal number, which has the same bits.
+        result
+    };
+
+    Ok(sign_bit | bits)
+}
@@ -0,0 +1,93 @@
+/// Declares a `static` regex that is only built on first use.
+///
+/// Uses `lazy_static` with the `std` feature and a [`LazyRegex`](crate::LazyRegex) without it.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "std")]
+macro_rules! lazy_regex {
+    ($name: ident: $ty: ty = $init: expr) => {
+        $crate::lazy_static::lazy_static! {
+            static ref $name: $ty = $init;
+        }
+    };
+}
+
+/// Declares a `static` regex that is only built on first use.
+///
+/// Uses `lazy_static` with the `std` feature and a [`LazyRegex`](crate::LazyRegex) without it.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "std"))]
+macro_rules! lazy_regex {
+    ($name: ident: $ty: ty = $init: expr) => {
+        static $name: $crate::LazyRegex<$ty> = $crate::LazyRegex::new(|| $init);
+    };
+}
+
+#[cfg(not(feature = "std"))]
+pub use no_std::LazyRegex;
+
+#[cfg(not(feature = "std"))]
+#[allow(unsafe_code)]
+mod no_std {
+    use alloc::boxed::Box;
+    use core::fmt;
+    use core::marker::PhantomData;
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    /// A regex (or the result of building one) that is built on first use, which replaces
+    /// `lazy_static` without `std`.
+    ///
+    /// The initialization is race-based: threads that access the regex at the same time might each
+    /// build it, but only the first one is kept and the others are dropped.
+    pub struct LazyRegex<T> {
+        regex: AtomicPtr<T>,
+        init: fn() -> T,
+        /// only `Sync` if `T` is, since references to the value are shared between threads
+        _marker: PhantomData<T>,
+    }
+
+    impl<T> LazyRegex<T> {
+        /// Creates a regex that is built by `init` on first use
+        pub const fn new(init: fn() -> T) -> Self {
+            Self {
+                regex: AtomicPtr::new(ptr::null_mut()),
+                init,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl<T> core::ops::Deref for LazyRegex<T> {
+        type Target = T;
+        fn deref(&self) -> &T {
+            let mut regex = self.regex.load(Ordering::Acquire);
+            if regex.is_null() {
+                let new = Box::into_raw(Box::new((self.init)()));
+                let res = self.regex.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+                regex = match res {
+                    Ok(_) => new,
+                    Err(existing) => {
+                        // SAFETY: `new` was created above and never shared
+                        drop(unsafe { Box::from_raw(new) });
+                        existing
+                    }
+                };
+            }
+            // SAFETY: `regex` is not null, and a stored regex is never mutated or freed
+            unsafe { &*regex }
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for LazyRegex<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(&**self, f)
+        }
+    }
+}
@@ -1,3 +1,4 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(
     missing_docs,
     missing_debug_implementations,
@@ -57,18 +58,71 @@
 //! with no drawbacks in functionality or performance. The only difference is the compiler errors
 //! that you get while writing format strings.
 
+#[doc(hidden)]
+pub extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use core::error;
+#[doc(hidden)]
+pub use error::Error as ErrorTrait;
+#[cfg(feature = "std")]
+use std::error; // for generated code, which has to work with and without std
+
 mod macros;
 pub use macros::*;
 
 mod regex_representation;
 pub use regex_representation::*;
 
+mod regex_options;
+pub use regex_options::RegexOptions;
+
 mod from_scanf;
 pub use from_scanf::*;
 
+mod from_scanf_with_options;
+pub use from_scanf_with_options::*;
+
 mod types;
 pub use types::*;
 
+mod radix;
+pub use radix::*;
+
+mod hex_float;
+#[doc(hidden)]
+pub use hex_float::HexFloat;
+
+mod captures;
+pub use captures::Captures;
+
+mod scan_buffer;
+pub use scan_buffer::ScanBuffer;
+
+mod capture_groups;
+#[doc(hidden)]
+pub use capture_groups::{capture_groups, extra_captures};
+
+mod one_of;
+pub use one_of::*;
+
+mod scanner;
+#[doc(hidden)]
+pub use scanner::{parse_str, scan_end, scan_end_str, scan_int, scan_literal, scan_str, Scan};
+
+mod lazy_regex;
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+pub use lazy_regex::LazyRegex;
+
+mod quoted;
+#[doc(hidden)]
+pub use quoted::unescape_quoted;
+
+mod balanced;
+#[doc(hidden)]
+pub use balanced::match_balanced;
+
 pub mod errors;
 #[doc(inline)]
 pub use errors::Error;
@@ -80,10 +134,17 @@ pub mod changelog {}
 
 #[doc(hidden)]
 pub use const_format;
+#[cfg(feature = "fancy")]
+#[doc(hidden)]
+pub use fancy_regex;
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use lazy_static;
 #[doc(hidden)]
 pub use regex;
+#[cfg(feature = "lite")]
+#[doc(hidden)]
+pub use regex_lite;
 
 #[allow(unused_imports)]
-use std::str::FromStr; // for links in the documentation
+use core::str::FromStr; // for links in the documentation
@@ -5,21 +5,24 @@
 /// ## Signature
 /// ```ignore
 /// sscanf!(input: impl Deref<Target=str>, format: <literal>, Type...) -> Result<(Type...), sscanf::Error>
+/// sscanf!(input: impl Deref<Target=str>, format: <literal>, Type...; option = value, ...) -> Result<(Type...), sscanf::Error>
 /// ```
 ///
 /// ## Parameters
-/// * `input`: The string to parse. Can be anything that implements [`Deref<Target=str>`](std::ops::Deref)
+/// * `input`: The string to parse. Can be anything that implements [`Deref<Target=str>`](core::ops::Deref)
 ///   (e.g. `&str`, `String`, `Cow<str>`, etc. See examples below). Note that `sscanf` does not take
 ///   ownership of the input.
-/// * `format`: A literal string. No const or static allowed, just like with [`format!()`](std::format).
+/// * `format`: A literal string. No const or static allowed, just like with [`format!()`](alloc::format).
 /// * `Type...`: The types to parse. See [Custom Types](index.html#custom-types) for more information.
+/// * `option = value`: Optional settings for building the regex after a `;`, see
+///   [Regex Options](#regex-options).
 ///
 /// ## Return Value
-/// A [`Result`](std::result::Result) with a tuple of the parsed types or a [`sscanf::Error`](crate::errors::Error).
+/// A [`Result`](core::result::Result) with a tuple of the parsed types or a [`sscanf::Error`](crate::errors::Error).
 /// Note that an error usually indicates that the input didn't match the format string, making the
-/// returned [`Result`](std::result::Result) functionally equivalent to an [`Option`](std::option::Option),
+/// returned [`Result`](core::result::Result) functionally equivalent to an [`Option`](core::option::Option),
 /// and most applications should treat it that way. An error is only useful when debugging
-/// custom implementations of [`FromStr`](std::str::FromStr) or [`FromScanf`](crate::FromScanf).
+/// custom implementations of [`FromStr`](core::str::FromStr) or [`FromScanf`](crate::FromScanf).
 /// See [`sscanf::Error`](crate::errors::Error) for more information.
 ///
 /// ## Details
@@ -31,7 +34,7 @@
 /// Placeholders within the format string are marked with `{}`. Any `'{'` or `'}'` that should not be
 /// treated as placeholders need to be escaped by writing `'{{'` or `'}}'`. For every placeholder there
 /// has to be a type name inside the `{}` or exactly one type in the parameters after the format
-/// string. Types can be referenced by indices in the placeholder, similar to [`format!()`](std::fmt).
+/// string. Types can be referenced by indices in the placeholder, similar to [`format!()`](core::fmt).
 ///
 /// Any additional formatting options are placed behind a `:`. For a list of options, see
 /// the [crate root documentation](index.html#format-options).
@@ -65,6 +68,32 @@
 /// ```
 ///
 /// More Examples can be seen in the crate root documentation.
+///
+/// ## Regex Options
+/// The regex of a format is built with the default settings of the [`regex`](crate::regex)
+/// crate, which limits the size of the compiled regex. Very large formats, like those of deeply
+/// nested derived enums, can exceed that limit and fail to build, which returns
+/// [`Error::InvalidRegex`](crate::Error::InvalidRegex). The options after a `;` are
+/// passed on to the [`RegexBuilder`](crate::regex::RegexBuilder), see [`RegexOptions`](crate::RegexOptions):
+/// - `size_limit = <bytes>`: the size limit of the compiled regex
+/// - `dfa_size_limit = <bytes>`: the cache size of the lazy DFA
+/// - `nest_limit = <depth>`: the nesting limit of the regex syntax
+/// - `unicode = <bool>`: Unicode mode, which makes `\d` match non-ASCII digits. Placeholders
+///   that can match any character, like `{str}`, `{char}` or `{str:[^,]}`, are put into a
+///   `(?u:...)` group with `unicode = false`, so that they still match whole characters
+///
+/// With the `lite` feature, formats with `dfa_size_limit` or `unicode` are built with `regex`
+/// instead of `regex-lite`, which has no such settings.
+///
+/// The regex is stored in a `static`, so the values have to be constants:
+/// ```
+/// # use sscanf::sscanf;
+/// const LIMIT: usize = 50_000_000;
+/// let parsed = sscanf!("1,2", "{u8},{u8}"; size_limit = LIMIT, unicode = false);
+/// assert_eq!(parsed.unwrap(), (1, 2));
+/// ```
+/// Formats with options are always matched by the regex, never by the generated code for
+/// simple formats.
 pub use sscanf_macro::sscanf;
 
 #[doc(hidden)]
@@ -78,11 +107,15 @@ pub use sscanf_macro::sscanf as scanf;
 /// ```
 ///
 /// ## Parameters
-/// * `format`: A literal string. No const or static allowed, just like with [`format!()`](std::format).
+/// * `format`: A literal string. No const or static allowed, just like with [`format!()`](alloc::format).
 /// * `Type...`: The types to parse. See [Custom Types](index.html#custom-types) for more information.
 ///
 /// Returns: A reference to the generated [`Regex`](regex::Regex).
 ///
+/// ## Panics
+/// Panics if the regex cannot be built, for example because it exceeds the `size_limit` of the
+/// [`RegexOptions`](crate::RegexOptions) of one of the types.
+///
 /// The Placeholders can be obtained by capturing the Regex and using the 1-based index of the Group.
 ///
 /// ## Examples
@@ -142,6 +175,36 @@ pub use sscanf_macro::sscanf_unescaped;
 #[doc(hidden)]
 pub use sscanf_macro::sscanf_unescaped as scanf_unescaped;
 
+/// Same as [`sscanf`], but reuses the memory of a [`ScanBuffer`](crate::ScanBuffer) between calls.
+///
+/// ## Signature
+/// ```ignore
+/// sscanf_with!(buffer: &mut ScanBuffer, input: impl Deref<Target=str>, format: <literal>, Type...) -> Result<(Type...), sscanf::Error>
+/// ```
+///
+/// Every call of [`sscanf`] that has to run its regex allocates space for the positions of the
+/// capture groups. When parsing many lines in a loop, `sscanf_with` takes that space from the
+/// buffer instead and puts it back afterwards, so that only the first call for each format
+/// allocates. Everything else, including the result, is identical to [`sscanf`].
+///
+/// Owned types like `String` in the output are still allocated on every call. Formats that use
+/// `{=expr}` build their regex on every call and can't use the buffer.
+///
+/// ```
+/// use sscanf::{sscanf_with, ScanBuffer};
+///
+/// let mut buffer = ScanBuffer::new();
+/// let mut points = vec![];
+/// for line in "(1.5, 2)\n(3, -4.25)".lines() {
+///     points.push(sscanf_with!(&mut buffer, line, "({f32}, {f32})").unwrap());
+/// }
+/// assert_eq!(points, [(1.5, 2.0), (3.0, -4.25)]);
+/// ```
+pub use sscanf_macro::sscanf_with;
+
+#[doc(hidden)]
+pub use sscanf_macro::sscanf_with as scanf_with;
+
 /// A derive macro for [`FromScanf`](crate::FromScanf).
 ///
 /// ## For structs
@@ -185,22 +248,32 @@ pub use sscanf_macro::sscanf_unescaped as scanf_unescaped;
 ///   `"{a} {b:/.*?/} {c}"`. All fields that are not annotated with `default` must appear exactly
 ///   once in the format string. Indices can be omitted if the fields are in the same order as the
 ///   placeholders `{}` in the format string. So, the above example could also be written as
-///   `"{} {:/.*?/} {}"`.
+///   `"{} {:/.*?/} {}"`. Like in [`sscanf`], `{=N}` has to be equal to the `N`th placeholder, and
+///   `{=<field>}` has to be equal to the placeholder of a field, like in `"<{tag}>{text}</{=tag}>"`.
+///   Since this is checked after the regex matched, an enum doesn't try the next variant if the
+///   values differ. `{=expr}` placeholders can't be used, because the regex is built at compile time.
 /// - `format_unescaped`: Same as `format`, but allows use of Regex in the format String. See
 ///   [`sscanf_unescaped`] for more information.
 /// - `transparent`: If the struct has exactly one field, the struct will be constructed from the
 ///   field directly. This is useful for newtype structs, where the struct is just a wrapper around
 ///   another type. The field has to implement [`FromScanf`](crate::FromScanf).
 ///
-/// Note that only one of the above attributes can be used on a struct. The `format = ` part can
-/// be omitted, so `#[sscanf("<format>")]` is also valid. In this case, the distinction between
-/// `format` and `format_unescaped` is made by using a regular string literal for `format` and a
-/// raw string literal (starting with `r#"` or `r#"`) for `format_unescaped`.
+/// - `regex_options(<option> = <value>, ...)`: Options for building the regex in
+///   [`FromScanf::from_str`](crate::FromScanf::from_str), with the same names as the
+///   [regex options of `sscanf`](macro.sscanf.html#regex-options). This can be combined with the
+///   other attributes, and also be used on enums. Calls to [`sscanf`] that contain the type use their
+///   own options instead.
+///
+/// Note that only one of the above attributes, apart from `regex_options`, can be used on a
+/// struct. The `format = ` part can be omitted, so `#[sscanf("<format>")]` is also valid. In this
+/// case, the distinction between `format` and `format_unescaped` is made by using a regular string
+/// literal for `format` and a raw string literal (starting with `r#"` or `r#"`) for
+/// `format_unescaped`.
 ///
 /// #### On the fields
 /// - `default` or `default = <expression>`: Marks the field to be set from a default value rather than the input string. A
-///   simple `#[sscanf(default)]` will set the field to [`Default::default()`](std::default::Default).
-///   If the field type doesn't implement [`Default`](std::default::Default), the attribute can
+///   simple `#[sscanf(default)]` will set the field to [`Default::default()`](core::default::Default).
+///   If the field type doesn't implement [`Default`](core::default::Default), the attribute can
 ///   take an expression that will be evaluated to get the default value. The expression can be
 ///   any code, including function calls or `{ <code> }` blocks, as long as they can be assigned
 ///   to the field type.
@@ -208,12 +281,12 @@ pub use sscanf_macro::sscanf_unescaped as scanf_unescaped;
 ///   a closure that takes the matched type as input and returns the field type. The type of the
 ///   parameter of the closure has to be explicitly specified, since it is needed to generate the
 ///   matching code.
-/// - `filter_map = |<param>: <type>| <conversion>`: Same as `map`, but the closure returns an [`Option`](std::option::Option) instead
-///   of the field type. If the closure returns [`None`](std::option::Option::None), the parsing
+/// - `filter_map = |<param>: <type>| <conversion>`: Same as `map`, but the closure returns an [`Option`](core::option::Option) instead
+///   of the field type. If the closure returns [`None`](core::option::Option::None), the parsing
 ///   fails.
 /// - `from = <type>`: Allows matching against a different type than the field type. The `from` attribute
 ///   takes a Type as input, which implements [`FromScanf`](crate::FromScanf) and can be converted
-///   to the field type using [`From`](std::convert::From).
+///   to the field type using [`From`](core::convert::From).
 /// - `try_from = <type>`: Same as `from`, but the conversion can fail. If the conversion fails,
 ///   the parsing fails.
 ///
@@ -263,6 +336,7 @@ pub use sscanf_macro::sscanf_unescaped as scanf_unescaped;
 ///   - `"SCREAMING_SNAKE_CASE"`
 ///   - `"kebab-case"`
 ///   - `"SCREAMING-KEBAB-CASE"`
+/// - `regex_options(<option> = <value>, ...)`: Same as for structs.
 ///
 /// #### On the variants
 ///
@@ -300,7 +374,7 @@ pub use sscanf_macro::sscanf_unescaped as scanf_unescaped;
 ///    string have to have a regex (`{:/.../}) attached to them.
 /// 2. The type bounds on `T` have to contain all of those exact bounds.
 ///
-/// Any `T` has to be constructed by [`FromStr`](std::str::FromStr) from what is matched by the
+/// Any `T` has to be constructed by [`FromStr`](core::str::FromStr) from what is matched by the
 /// specified regex, making this setup virtually useless for all but a few selected types. Since
 /// the generic parameter has to be specified in the actual `sscanf` call, it is usually better
 /// to just use a concrete type in the struct itself.
@@ -314,3 +388,19 @@ pub use sscanf_macro::FromScanf;
 
 #[doc(hidden)]
 pub use sscanf_macro::FromScanf as FromSscanf;
+
+/// A derive macro for [`ScanfRadix`](trait.ScanfRadix.html) on newtypes.
+///
+/// Works on structs with exactly one field, which has to implement `ScanfRadix` itself. All
+/// conversions are forwarded to that field.
+///
+/// ```
+/// #[derive(sscanf::ScanfRadix)]
+/// struct Id {
+///     id: std::num::NonZeroU32,
+/// }
+///
+/// let parsed = sscanf::sscanf!("id: 1f", "id: {Id:x}").unwrap();
+/// assert_eq!(parsed.id.get(), 0x1f);
+/// ```
+pub use sscanf_macro::ScanfRadix;
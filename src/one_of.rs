@@ -0,0 +1,60 @@
+use core::fmt;
+
+macro_rules! one_of {
+    ($(#[$attr: meta])* $name: ident, $($variant: ident),+) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $name<$($variant),+> {
+            $(
+                #[allow(missing_docs)]
+                $variant($variant),
+            )+
+        }
+
+        impl<$($variant: fmt::Display),+> fmt::Display for $name<$($variant),+> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $($name::$variant(value) => value.fmt(f),)+
+                }
+            }
+        }
+    };
+}
+
+one_of!(
+    /// The value of a placeholder with multiple types, like `{u32|str}`.
+    ///
+    /// The regex of the placeholder is an alternation of the regexes of all types. The variant is
+    /// the first type whose regex matched, so more specific types should come first. Only the
+    /// matching type is parsed.
+    ///
+    /// ```
+    /// use sscanf::{sscanf, OneOf2};
+    ///
+    /// let parsed = sscanf!("timeout=30", "timeout={u64|str}");
+    /// assert_eq!(parsed.unwrap(), OneOf2::A(30));
+    ///
+    /// let parsed = sscanf!("timeout=never", "timeout={u64|str}");
+    /// assert_eq!(parsed.unwrap(), OneOf2::B("never"));
+    /// ```
+    ///
+    /// The enum can also be used as a type in the arguments, e.g.
+    /// `sscanf!(input, "timeout={}", OneOf2<u64, &str>)`.
+    OneOf2, A, B
+);
+one_of!(
+    /// The value of a placeholder with three types, like `{u32|f64|str}`. See [`OneOf2`].
+    OneOf3, A, B, C
+);
+one_of!(
+    /// The value of a placeholder with four types. See [`OneOf2`].
+    OneOf4, A, B, C, D
+);
+one_of!(
+    /// The value of a placeholder with five types. See [`OneOf2`].
+    OneOf5, A, B, C, D, E
+);
+one_of!(
+    /// The value of a placeholder with six types. See [`OneOf2`].
+    OneOf6, A, B, C, D, E, F
+);
@@ -0,0 +1,70 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use crate::errors::InvalidEscapeError;
+
+/// Removes the quotes around a string matched by the `{:q}` option and resolves the escape
+/// sequences.
+///
+/// Supported escapes are `\\`, `\"`, `\'`, `\n`, `\r`, `\t`, `\0` and `\u{...}`. Without any
+/// escapes, the content is borrowed from the input.
+#[doc(hidden)]
+pub fn unescape_quoted(src: &str) -> Result<Cow<'_, str>, InvalidEscapeError> {
+    // the regex guarantees the quotes, which are always one byte long
+    let content = &src[1..src.len() - 1];
+    if !content.contains('\\') {
+        return Ok(Cow::Borrowed(content));
+    }
+
+    let mut ret = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(backslash) = rest.find('\\') {
+        ret.push_str(&rest[..backslash]);
+        let sequence = &rest[backslash..];
+
+        // the regex guarantees that there is a character after every backslash
+        let escaped = sequence[1..].chars().next().unwrap();
+        let mut len = 1 + escaped.len_utf8();
+        let c = match escaped {
+            '\\' | '"' | '\'' => escaped,
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                let code = sequence[len..]
+                    .strip_prefix('{')
+                    .and_then(|s| s.find('}').map(|end| &s[..end]));
+                let c = code
+                    .and_then(|code| u32::from_str_radix(code, 16).ok())
+                    .and_then(core::char::from_u32);
+                match (code, c) {
+                    (Some(code), Some(c)) => {
+                        len += code.len() + 2; // +2 for the braces
+                        c
+                    }
+                    _ => return Err(InvalidEscapeError::new(&sequence[..len])),
+                }
+            }
+            _ => return Err(InvalidEscapeError::new(&sequence[..len])),
+        };
+        ret.push(c);
+        rest = &sequence[len..];
+    }
+    ret.push_str(rest);
+    Ok(Cow::Owned(ret))
+}
+
+#[test]
+fn unescape() {
+    assert_eq!(unescape_quoted(r#""abc""#).unwrap(), Cow::Borrowed("abc"));
+    assert_eq!(unescape_quoted(r#"'a\'b'"#).unwrap(), "a'b");
+    let unescaped = unescape_quoted(r#""\\ \" \n\t\u{1F600}\u{e9}!""#).unwrap();
+    assert_eq!(unescaped, "\\ \" \n\t\u{1F600}\u{e9}!");
+    assert_eq!(unescape_quoted(r#""\x""#).unwrap_err().sequence, r"\x");
+    assert_eq!(
+        unescape_quoted(r#""\u{110000}""#).unwrap_err().sequence,
+        r"\u"
+    );
+    assert_eq!(unescape_quoted(r#""\u1234""#).unwrap_err().sequence, r"\u");
+}
@@ -0,0 +1,156 @@
+use core::num::*;
+
+/// A trait for integer types that can be parsed with the radix format options (`{:x}`, `{:o}`,
+/// `{:b}`, `{:r2}` - `{:r36}`).
+///
+/// Implemented for all primitive integers and their `NonZero` counterparts. Since the format
+/// options rely on this trait instead of the name of the type, they also work on type aliases
+/// like `type Addr = u64;` or `std::primitive::u64`.
+///
+/// ## Deriving
+/// Newtypes around a type that implements `ScanfRadix` can derive the trait, which forwards
+/// everything to the inner type:
+/// ```
+/// #[derive(sscanf::ScanfRadix, Debug, PartialEq)]
+/// struct Reg(u16);
+///
+/// let input = "r1 = 0xbeef";
+/// let parsed = sscanf::sscanf!(input, "r1 = {Reg:#x}");
+/// assert_eq!(parsed.unwrap(), Reg(0xbeef));
+/// ```
+/// The derive works on any struct with exactly one field, named or unnamed.
+///
+/// ## Implementing the Trait
+/// A manual implementation has to specify the number of bits of the type, which is used to limit
+/// the number of digits in the regex, and whether negative numbers are possible:
+/// ```
+/// struct Celsius(i16);
+///
+/// impl sscanf::ScanfRadix for Celsius {
+///     const BITS: u32 = 16;
+///     const SIGNED: bool = true;
+///     type Err = std::num::ParseIntError;
+///     fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+///         i16::from_str_radix(src, radix).map(Celsius)
+///     }
+/// }
+///
+/// let parsed = sscanf::sscanf!("-0x1f", "{Celsius:x}").unwrap();
+/// assert_eq!(parsed.0, -0x1f);
+/// ```
+pub trait ScanfRadix: Sized {
+    /// The number of bits of the type, which determines the maximum number of digits.
+    const BITS: u32;
+    /// Whether the type can be negative, which allows a `-` sign in front of the number.
+    const SIGNED: bool;
+    /// The error returned by the conversions.
+    type Err: crate::error::Error + 'static;
+
+    /// Parses a number in the given radix. `src` has no prefix, but may start with a sign.
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err>;
+
+    /// Parses the digits as the two's complement bit pattern of the type. Used by the `~` modifier,
+    /// as in `{:x~}`. `src` has no prefix and no sign.
+    ///
+    /// The default implementation is the same as [`from_str_radix`](Self::from_str_radix), which
+    /// is correct for unsigned types. Signed types should parse the digits as their unsigned
+    /// counterpart and reinterpret the bits.
+    fn from_twos_complement_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+        Self::from_str_radix(src, radix)
+    }
+}
+
+macro_rules! impl_radix {
+    (unsigned; $($ty: ty),+) => {
+        $(impl ScanfRadix for $ty {
+            const BITS: u32 = <$ty>::BITS;
+            const SIGNED: bool = false;
+            type Err = ParseIntError;
+            fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+                <$ty>::from_str_radix(src, radix)
+            }
+        })+
+    };
+    (signed; $(($ty: ty, $unsigned: ty)),+) => {
+        $(impl ScanfRadix for $ty {
+            const BITS: u32 = <$ty>::BITS;
+            const SIGNED: bool = true;
+            type Err = ParseIntError;
+            fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+                <$ty>::from_str_radix(src, radix)
+            }
+            fn from_twos_complement_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+                <$unsigned>::from_str_radix(src, radix).map(|bits| bits as $ty)
+            }
+        })+
+    };
+    (non_zero; $(($ty: ty, $inner: ty)),+) => {
+        $(impl ScanfRadix for $ty {
+            const BITS: u32 = <$inner as ScanfRadix>::BITS;
+            const SIGNED: bool = <$inner as ScanfRadix>::SIGNED;
+            type Err = ParseIntError;
+            fn from_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+                let n = <$inner as ScanfRadix>::from_str_radix(src, radix)?;
+                // ParseIntError cannot be constructed manually, so the "zero" error is obtained
+                // from the FromStr implementation instead
+                <$ty>::new(n).ok_or_else(|| "0".parse::<$ty>().unwrap_err())
+            }
+            fn from_twos_complement_str_radix(src: &str, radix: u32) -> Result<Self, Self::Err> {
+                let n = <$inner as ScanfRadix>::from_twos_complement_str_radix(src, radix)?;
+                <$ty>::new(n).ok_or_else(|| "0".parse::<$ty>().unwrap_err())
+            }
+        })+
+    };
+}
+
+impl_radix!(unsigned; u8, u16, u32, u64, u128, usize);
+impl_radix!(signed;
+    (i8, u8),
+    (i16, u16),
+    (i32, u32),
+    (i64, u64),
+    (i128, u128),
+    (isize, usize)
+);
+impl_radix!(non_zero;
+    (NonZeroU8, u8),
+    (NonZeroU16, u16),
+    (NonZeroU32, u32),
+    (NonZeroU64, u64),
+    (NonZeroU128, u128),
+    (NonZeroUsize, usize),
+    (NonZeroI8, i8),
+    (NonZeroI16, i16),
+    (NonZeroI32, i32),
+    (NonZeroI64, i64),
+    (NonZeroI128, i128),
+    (NonZeroIsize, isize)
+);
+
+/// The maximum number of digits that a number with `bits` bits has in the given `radix`.
+///
+/// Used to limit the length of the regex generated for radix options.
+#[doc(hidden)]
+pub const fn radix_digits(bits: u32, radix: u32) -> usize {
+    if bits == 0 {
+        return 1;
+    }
+    let mut max = u128::MAX >> (u128::BITS - bits);
+    let mut digits = 0;
+    while max > 0 {
+        max /= radix as u128;
+        digits += 1;
+    }
+    digits
+}
+
+#[test]
+fn digits() {
+    assert_eq!(radix_digits(8, 2), 8);
+    assert_eq!(radix_digits(8, 10), 3);
+    assert_eq!(radix_digits(8, 16), 2);
+    assert_eq!(radix_digits(16, 8), 6);
+    assert_eq!(radix_digits(32, 36), 7);
+    assert_eq!(radix_digits(128, 16), 32);
+    assert_eq!(radix_digits(128, 10), 39);
+}
@@ -0,0 +1,114 @@
+use regex::{Regex, RegexBuilder};
+
+/// Options for building the regex of a format, which map onto [`RegexBuilder`].
+///
+/// These are usually not created by hand, but specified after the types of a [`sscanf!`](crate::sscanf)
+/// call or in a `#[sscanf(regex_options(...))]` attribute of a type that
+/// [`derive`s `FromScanf`](crate::FromScanf):
+/// ```
+/// # use sscanf::sscanf;
+/// let parsed = sscanf!("7 + 3", "{u8} + {u8}"; size_limit = 100_000, unicode = false);
+/// assert_eq!(parsed.unwrap(), (7, 3));
+///
+/// #[derive(sscanf::FromScanf, Debug, PartialEq)]
+/// #[sscanf(format = "({x},{y})", regex_options(nest_limit = 500))]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+/// use sscanf::FromScanf;
+/// assert_eq!(Point::from_str("(1,-2)").unwrap(), Point { x: 1, y: -2 });
+/// ```
+///
+/// Options that are not set keep the default of [`RegexBuilder`]. With the `lite` feature, formats
+/// with `dfa_size_limit` or `unicode` are built with `regex`, because `regex-lite` has no such
+/// settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexOptions {
+    size_limit: Option<usize>,
+    dfa_size_limit: Option<usize>,
+    nest_limit: Option<u32>,
+    unicode: Option<bool>,
+}
+
+impl RegexOptions {
+    /// Creates options that don't change any defaults
+    pub const fn new() -> Self {
+        Self {
+            size_limit: None,
+            dfa_size_limit: None,
+            nest_limit: None,
+            unicode: None,
+        }
+    }
+
+    /// Sets the approximate size limit of the compiled regex in bytes, see
+    /// [`RegexBuilder::size_limit`]. Large formats, like those of deeply nested derived enums,
+    /// might need a higher limit.
+    pub const fn size_limit(mut self, bytes: usize) -> Self {
+        self.size_limit = Some(bytes);
+        self
+    }
+
+    /// Sets the approximate size of the cache of the lazy DFA in bytes, see
+    /// [`RegexBuilder::dfa_size_limit`].
+    pub const fn dfa_size_limit(mut self, bytes: usize) -> Self {
+        self.dfa_size_limit = Some(bytes);
+        self
+    }
+
+    /// Sets the nesting limit of the regex syntax, see [`RegexBuilder::nest_limit`]
+    pub const fn nest_limit(mut self, limit: u32) -> Self {
+        self.nest_limit = Some(limit);
+        self
+    }
+
+    /// Enables or disables Unicode mode, see [`RegexBuilder::unicode`].
+    ///
+    /// Without Unicode, `\d` and `\w` only match ASCII characters. Regexes that could match
+    /// parts of a multi-byte character, like the `.` in the regex of `str`, `String` or `char`,
+    /// cannot be built. `sscanf!` and `#[derive(FromScanf)]` reject such placeholders at compile
+    /// time, the regex of other types returns an [`Error::InvalidRegex`](crate::Error::InvalidRegex).
+    pub const fn unicode(mut self, yes: bool) -> Self {
+        self.unicode = Some(yes);
+        self
+    }
+
+    /// Builds `regex` with these options
+    pub fn build(&self, regex: &str) -> Result<Regex, regex::Error> {
+        let mut builder = RegexBuilder::new(regex);
+        if let Some(bytes) = self.size_limit {
+            builder.size_limit(bytes);
+        }
+        if let Some(bytes) = self.dfa_size_limit {
+            builder.dfa_size_limit(bytes);
+        }
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        if let Some(yes) = self.unicode {
+            builder.unicode(yes);
+        }
+        builder.build()
+    }
+
+    /// Whether `regex-lite` has all of the options that are set
+    #[doc(hidden)]
+    pub const fn lite_supports(&self) -> bool {
+        self.dfa_size_limit.is_none() && self.unicode.is_none()
+    }
+
+    /// Builds `regex` with `regex-lite`, which only has the `size_limit` and `nest_limit` options
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn build_lite(&self, regex: &str) -> Result<regex_lite::Regex, regex_lite::Error> {
+        let mut builder = regex_lite::RegexBuilder::new(regex);
+        if let Some(bytes) = self.size_limit {
+            builder.size_limit(bytes);
+        }
+        if let Some(limit) = self.nest_limit {
+            builder.nest_limit(limit);
+        }
+        builder.build()
+    }
+}
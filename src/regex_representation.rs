@@ -20,8 +20,9 @@ use const_format::formatcp;
 /// and should be preferred in most cases.
 ///
 /// If you do need to implement this trait yourself, note the following:
-/// - The regex cannot contain any capture groups (round brackets). If you need to use `( )` in your
-///   regex, use `(?: )` instead to make it non-capturing.
+/// - Capture groups (round brackets) in the regex are skipped when parsing the type with its
+///   [`FromStr`](core::str::FromStr) implementation. Non-capturing groups `(?: )` are still slightly
+///   faster, since the regex engine doesn't have to track them.
 /// - Using a raw string literal (`r"..."`) is recommended to avoid having to escape backslashes.
 /// - The [`const_format`] crate can be used to combine multiple
 ///   strings into one, which is useful for complex regexes. This can also be used to combine the
@@ -97,6 +98,13 @@ use const_format::formatcp;
 pub trait RegexRepresentation {
     /// A regular Expression that exactly matches any String representation of the implementing Type
     const REGEX: &'static str;
+
+    /// Options for building [`REGEX`](Self::REGEX) in [`FromScanf::from_str`](crate::FromScanf::from_str).
+    ///
+    /// Set with `#[sscanf(regex_options(...))]` when deriving [`FromScanf`](crate::FromScanf).
+    /// These options are not applied to the regex of a [`sscanf!`](crate::sscanf) call that uses
+    /// the type, which takes its own options instead.
+    const REGEX_OPTIONS: crate::RegexOptions = crate::RegexOptions::new();
 }
 
 // float syntax: https://doc.rust-lang.org/std/primitive.f32.html#grammar
@@ -153,7 +161,8 @@ macro_rules! impl_num {
     };
 }
 
-use std::num::*;
+use alloc::string::String;
+use core::num::*;
 
 impl_num!("any positive", r"\+?\d{1,";
     (u8, 3),
@@ -233,6 +242,7 @@ impl RegexRepresentation for bool {
     const REGEX: &'static str = r"true|false";
 }
 
+#[cfg(feature = "std")]
 impl RegexRepresentation for std::path::PathBuf {
     /// Matches any sequence of Characters.
     ///
@@ -264,5 +274,6 @@ fn no_capture_groups() {
     check!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize);
     check!(f32, f64);
     check!(String, str, char, bool);
+    #[cfg(feature = "std")]
     check!(std::path::PathBuf);
 }
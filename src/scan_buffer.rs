@@ -0,0 +1,84 @@
+use alloc::vec::Vec;
+use regex::{CaptureLocations, Regex};
+
+/// Memory that is reused between calls of [`sscanf_with!`](crate::sscanf_with).
+///
+/// [`sscanf!`](crate::sscanf) allocates the positions of the capture groups on every call that
+/// uses a regex. `sscanf_with!` keeps them in this buffer instead, so that repeated calls don't
+/// allocate, apart from owned types like `String` in the output. One buffer can be used for any
+/// number of different formats.
+///
+/// ```
+/// use sscanf::{sscanf_with, ScanBuffer};
+///
+/// let input = "x=1.5 y=-2\nx=0.5 y=7";
+/// let mut buffer = ScanBuffer::new();
+/// let mut sum = 0.0;
+/// for line in input.lines() {
+///     let (x, y) = sscanf_with!(&mut buffer, line, "x={f64} y={i32}").unwrap();
+///     sum += x * y as f64;
+/// }
+/// assert_eq!(sum, 0.5);
+/// ```
+#[derive(Debug, Default)]
+pub struct ScanBuffer {
+    /// the capture locations of every regex that was used with this buffer
+    locations: Vec<(&'static Regex, CaptureLocations)>,
+    /// the same for the regexes of the `lite` feature
+    #[cfg(feature = "lite")]
+    lite_locations: Vec<(&'static regex_lite::Regex, regex_lite::CaptureLocations)>,
+}
+
+impl ScanBuffer {
+    /// Creates an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the capture locations for `regex` out of the buffer, or creates new ones
+    #[doc(hidden)]
+    pub fn take_locations(&mut self, regex: &'static Regex) -> CaptureLocations {
+        let index = self
+            .locations
+            .iter()
+            .position(|(r, _)| core::ptr::eq(*r, regex));
+        match index {
+            Some(index) => self.locations.swap_remove(index).1,
+            None => regex.capture_locations(),
+        }
+    }
+
+    /// Returns the capture locations for `regex` to the buffer
+    #[doc(hidden)]
+    pub fn restore_locations(&mut self, regex: &'static Regex, locations: CaptureLocations) {
+        self.locations.push((regex, locations));
+    }
+
+    /// [`take_locations`](Self::take_locations) for `regex-lite`
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn take_lite_locations(
+        &mut self,
+        regex: &'static regex_lite::Regex,
+    ) -> regex_lite::CaptureLocations {
+        let index = self
+            .lite_locations
+            .iter()
+            .position(|(r, _)| core::ptr::eq(*r, regex));
+        match index {
+            Some(index) => self.lite_locations.swap_remove(index).1,
+            None => regex.capture_locations(),
+        }
+    }
+
+    /// [`restore_locations`](Self::restore_locations) for `regex-lite`
+    #[doc(hidden)]
+    #[cfg(feature = "lite")]
+    pub fn restore_lite_locations(
+        &mut self,
+        regex: &'static regex_lite::Regex,
+        locations: regex_lite::CaptureLocations,
+    ) {
+        self.lite_locations.push((regex, locations));
+    }
+}
@@ -0,0 +1,170 @@
+//! Building blocks for formats that are matched without a regex.
+//!
+//! Formats that only consist of literal text, integers and strings are matched by straight-line
+//! code that is generated by `sscanf!` and uses these functions. The result is always identical to
+//! the one of the regex. Any input that could behave differently, like non-ASCII digits that `\d`
+//! would match, returns [`Scan::Unsure`], in which case the regex is used instead.
+
+use alloc::boxed::Box;
+use core::str::FromStr;
+
+use crate::errors::{Error, FromStrFailedError};
+
+/// Result of matching (a part of) the input without a regex
+#[derive(Debug)]
+pub enum Scan<T> {
+    /// The input matched
+    Match(T),
+    /// The regex would not match the input either
+    NoMatch,
+    /// The input has to be matched by the regex
+    Unsure,
+}
+
+impl<T> Scan<T> {
+    /// Continues with `f` if the input matched so far
+    pub fn and_then<U, F: FnOnce(T) -> Scan<U>>(self, f: F) -> Scan<U> {
+        match self {
+            Scan::Match(value) => f(value),
+            Scan::NoMatch => Scan::NoMatch,
+            Scan::Unsure => Scan::Unsure,
+        }
+    }
+
+    /// Maps the matched value
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Scan<U> {
+        self.and_then(|value| Scan::Match(f(value)))
+    }
+}
+
+/// Strips the literal `prefix` from the input and returns the rest
+pub fn scan_literal<'t>(input: &'t str, prefix: &str) -> Scan<&'t str> {
+    match input.strip_prefix(prefix) {
+        Some(rest) => Scan::Match(rest),
+        None => Scan::NoMatch,
+    }
+}
+
+/// Matches an integer like `[-+]?\d{1,max_digits}`, followed by the literal `delimiter`.
+///
+/// `delimiter` must not start with a digit. Returns the integer and the rest after the delimiter.
+pub fn scan_int<'t>(
+    input: &'t str,
+    signed: bool,
+    max_digits: usize,
+    delimiter: &str,
+) -> Scan<(&'t str, &'t str)> {
+    let bytes = input.as_bytes();
+    let mut end = 0;
+    if let Some(b'+') = bytes.first() {
+        end += 1;
+    } else if let (Some(b'-'), true) = (bytes.first(), signed) {
+        end += 1;
+    }
+    let digits_start = end;
+    while end < bytes.len() && bytes[end].is_ascii_digit() {
+        end += 1;
+    }
+    // `\d` also matches non-ASCII digits
+    if let Some(c) = input[end..].chars().next() {
+        if !c.is_ascii() && c.is_numeric() {
+            return Scan::Unsure;
+        }
+    }
+    let digits = end - digits_start;
+    if digits == 0 || digits > max_digits {
+        return Scan::NoMatch;
+    }
+    scan_literal(&input[end..], delimiter).map(|rest| (&input[..end], rest))
+}
+
+/// Matches a string like `.+?`, followed by the literal `delimiter` and whatever `rest` matches.
+///
+/// Like the regex, the shortest string for which `rest` matches is used.
+pub fn scan_str<'t, T, F: FnMut(&'t str) -> Scan<T>>(
+    input: &'t str,
+    delimiter: &str,
+    mut rest: F,
+) -> Scan<(&'t str, T)> {
+    let mut start = 0;
+    while let Some(offset) = input[start..].find(delimiter) {
+        let end = start + offset;
+        if input[..end].contains('\n') {
+            break; // `.` does not match newlines
+        }
+        if end > 0 {
+            match rest(&input[end + delimiter.len()..]) {
+                Scan::Match(value) => return Scan::Match((&input[..end], value)),
+                Scan::NoMatch => {}
+                Scan::Unsure => return Scan::Unsure,
+            }
+        }
+        start = end + input[end..].chars().next().map_or(1, char::len_utf8);
+    }
+    Scan::NoMatch
+}
+
+/// Matches a string like `.+?` at the end of the input, followed by the literal `suffix`
+pub fn scan_end_str<'t>(input: &'t str, suffix: &str) -> Scan<&'t str> {
+    match input.strip_suffix(suffix) {
+        Some(s) if !s.is_empty() && !s.contains('\n') => Scan::Match(s),
+        _ => Scan::NoMatch,
+    }
+}
+
+/// Matches the end of the input
+pub fn scan_end(input: &str) -> Scan<()> {
+    if input.is_empty() {
+        Scan::Match(())
+    } else {
+        Scan::NoMatch
+    }
+}
+
+/// Parses a matched value with the same error as the [`FromScanf`](crate::FromScanf)
+/// implementation of [`FromStr`] types.
+pub fn parse_str<T>(src: &str) -> Result<T, Error>
+where
+    T: FromStr + 'static,
+    <T as FromStr>::Err: crate::error::Error + 'static,
+{
+    src.parse()
+        .map_err(FromStrFailedError::<T>::new)
+        .map_err(|e| Error::ParsingFailed(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unwrap<T>(scan: Scan<T>) -> Option<T> {
+        match scan {
+            Scan::Match(value) => Some(value),
+            Scan::NoMatch => None,
+            Scan::Unsure => panic!("unexpected Unsure"),
+        }
+    }
+
+    #[test]
+    fn int() {
+        assert_eq!(unwrap(scan_int("+12,x", false, 3, ",")), Some(("+12", "x")));
+        assert_eq!(unwrap(scan_int("-12,x", true, 3, ",")), Some(("-12", "x")));
+        assert_eq!(unwrap(scan_int("-12,x", false, 3, ",")), None);
+        assert_eq!(unwrap(scan_int("1234,x", false, 3, ",")), None);
+        assert_eq!(unwrap(scan_int("+,x", false, 3, ",")), None);
+        assert_eq!(unwrap(scan_int("12", false, 3, "")), Some(("12", "")));
+        assert!(matches!(scan_int("1٣,", false, 3, ","), Scan::Unsure));
+        assert!(matches!(scan_int("٣,", false, 3, ","), Scan::Unsure));
+        assert_eq!(unwrap(scan_int("1€", false, 3, "€")), Some(("1", "")));
+    }
+
+    #[test]
+    fn string() {
+        let rest = |s| scan_int(s, false, 3, "").and_then(|(n, rest)| scan_end(rest).map(|_| n));
+        assert_eq!(unwrap(scan_str("a-b-5", "-", rest)), Some(("a-b", "5")));
+        assert_eq!(unwrap(scan_str("-a-5", "-", rest)), Some(("-a", "5")));
+        assert_eq!(unwrap(scan_str("a\nb-5", "-", rest)), None);
+        assert_eq!(unwrap(scan_end_str("ab!", "!")), Some("ab"));
+        assert_eq!(unwrap(scan_end_str("!", "!")), None);
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(deprecated)]
 use crate::RegexRepresentation;
-use std::ops::*;
+use core::ops::*;
 
 macro_rules! impl_wrapper_ops {
     ($name: ty, $target: ty) => {
@@ -33,8 +33,8 @@ macro_rules! impl_wrapper_ops {
 #[deprecated(since = "0.4.0", note = "use f32 instead")]
 pub struct FullF32(pub f32);
 
-impl std::str::FromStr for FullF32 {
-    type Err = <f32 as std::str::FromStr>::Err;
+impl core::str::FromStr for FullF32 {
+    type Err = <f32 as core::str::FromStr>::Err;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         s.parse().map(Self)
     }
@@ -50,8 +50,8 @@ impl_wrapper_ops!(FullF32, f32);
 #[deprecated(since = "0.4.0", note = "use f64 instead")]
 pub struct FullF64(pub f64);
 
-impl std::str::FromStr for FullF64 {
-    type Err = <f64 as std::str::FromStr>::Err;
+impl core::str::FromStr for FullF64 {
+    type Err = <f64 as core::str::FromStr>::Err;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         s.parse().map(Self)
     }
@@ -78,8 +78,8 @@ impl_wrapper_ops!(FullF64, f64);
 )]
 pub struct HexNumber(pub usize);
 
-impl std::str::FromStr for HexNumber {
-    type Err = <usize as std::str::FromStr>::Err;
+impl core::str::FromStr for HexNumber {
+    type Err = <usize as core::str::FromStr>::Err;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s
             .strip_prefix("0x")
@@ -52,7 +52,7 @@ impl ValueSource {
         }
     }
 
-    fn get(&self, field_ty: &Type, matchers: &[Matcher]) -> TokenStream {
+    fn get(&self, field_ty: &Type) -> TokenStream {
         match self {
             ValueSource::Default { def, .. } => def
                 .as_ref()
@@ -60,21 +60,19 @@ impl ValueSource {
                 .unwrap_or_else(|| {
                     field_ty
                         .full_span()
-                        .apply(quote! { ::std::default::Default }, quote! { ::default() })
+                        .apply(quote! { ::core::default::Default }, quote! { ::default() })
                 }),
             ValueSource::Placeholder(i) => {
-                let matcher = &matchers[*i];
-                quote! { #matcher }
+                let value = placeholder_value(*i);
+                quote! { #value }
             }
         }
     }
+}
 
-    fn index_or(&self, n: usize) -> usize {
-        match self {
-            ValueSource::Default { .. } => n,
-            ValueSource::Placeholder(i) => *i,
-        }
-    }
+/// Name of the variable that contains the value of placeholder `i`
+fn placeholder_value(i: usize) -> syn::Ident {
+    quote::format_ident!("value_{}", i)
 }
 
 struct ValueConversion {
@@ -94,11 +92,11 @@ impl ValueConversion {
         use ValueConversionMethod::*;
         match with {
             From => FullSpan::from_spanned(from).apply(
-                quote! { <#to as ::std::convert::From<#from>> },
+                quote! { <#to as ::core::convert::From<#from>> },
                 quote! { ::from(#value) },
             ),
             TryFrom => FullSpan::from_spanned(from).apply(
-                quote! { <#to as ::std::convert::TryFrom<#from>> },
+                quote! { <#to as ::core::convert::TryFrom<#from>> },
                 quote! { ::try_from(#value)? },
             ),
             Map(closure) => {
@@ -111,7 +109,7 @@ impl ValueConversion {
             FilterMap(closure) => {
                 let span = closure.body.span();
                 quote::quote_spanned! {span=> {
-                    let f: fn(#from) -> ::std::option::Option<#to> = #closure;
+                    let f: fn(#from) -> ::core::option::Option<#to> = #closure;
                     f(#value).ok_or(::sscanf::errors::FilterMapNoneError {
                         field_name: stringify!(#field_name)
                     })?
@@ -121,9 +119,11 @@ impl ValueConversion {
     }
 }
 
+/// Returns the regex parts of the format and an expression that constructs `path` from `src`
 fn parse_format(
     attr: StructAttribute,
     raw_fields: syn::Fields,
+    path: TokenStream,
 ) -> Result<(RegexParts, TokenStream, HashSet<syn::Lifetime>)> {
     let (value, escape) = match attr.kind {
         StructAttributeKind::Format { value, escape } => (value, escape),
@@ -189,6 +189,12 @@ fn parse_format(
             if let Some(lt) = ty.lifetime() {
                 str_lifetimes.insert(lt.clone());
             }
+            // the types of a `OneOfN` field are matched directly from the input as well
+            for inner in ty_check::get_one_of(ty.inner()).unwrap_or_default() {
+                if let Some(lt) = TypeKind::from_ty(&inner).lifetime() {
+                    str_lifetimes.insert(lt.clone());
+                }
+            }
         }
 
         fields.push(Field {
@@ -199,14 +205,38 @@ fn parse_format(
         });
     }
 
-    let mut ph_to_field_map = vec![0; format.placeholders.len()];
+    let mut ph_to_field_map = vec![None; format.placeholders.len()];
+    let mut equal_to = vec![None; format.placeholders.len()];
+    let mut equal_to_fields = vec![];
     let mut error = Error::builder();
     for (ph_index, ph) in format.placeholders.iter().enumerate() {
         let name = match ph.ident.as_ref() {
+            Some(_) if ph.alternatives().is_some() => continue, // only matches literal text
             Some(name) => name,
             None => continue,
         };
 
+        match ph.equal_to() {
+            Ok(Some(n)) => {
+                equal_to[ph_index] = Some(n);
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error.push(e);
+                continue;
+            }
+        }
+        if let Some(field) = name.text().strip_prefix('=') {
+            if let Some(i) = field_map.get(field) {
+                equal_to_fields.push((ph_index, *i));
+            } else {
+                let msg = format!("field `{}` does not exist. `{{=expr}}` placeholders cannot be used here, because the regex has to be built at compile time", field);
+                error.push(name.error(msg)); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+            }
+            continue;
+        }
+
         let index = if let Some(i) = field_map.get(name.text()) {
             *i
         } else {
@@ -230,7 +260,7 @@ fn parse_format(
             continue;
         }
         field.value_source = Some(ValueSource::Placeholder(ph_index));
-        ph_to_field_map[ph_index] = index;
+        ph_to_field_map[ph_index] = Some(index);
     }
 
     let mut unused_field_iter = fields
@@ -251,7 +281,7 @@ fn parse_format(
         };
         // field.ph_index is guaranteed to be None because of the iterator filter
         field.value_source = Some(ValueSource::Placeholder(ph_index));
-        ph_to_field_map[ph_index] = index;
+        ph_to_field_map[ph_index] = Some(index);
     }
 
     for (_, unused) in unused_field_iter {
@@ -263,37 +293,75 @@ The syntax for default values is: `#[sscanf(default)]` to use Default::default()
         error.with_spanned(&unused.ident, msg); // checked in tests/fail/<channel>/derive_placeholders.rs
     }
 
-    error.ok_or_build()?;
+    // `{=field}` refers to the placeholder of the field
+    for (ph_index, field_index) in equal_to_fields {
+        if let Some(ValueSource::Placeholder(i)) = fields[field_index].value_source {
+            equal_to[ph_index] = Some(i);
+        } else {
+            let msg = "`{=field}` has to refer to a field that is read from a placeholder";
+            error.push(format.placeholders[ph_index].src.error(msg)); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+        }
+    }
 
-    let ph_types = ph_to_field_map
-        .iter()
-        .map(|i| fields[*i].ty.clone())
-        .collect::<Vec<_>>();
-    let regex_parts = RegexParts::new(&format, &ph_types)?;
+    let mut ph_types: Vec<Type> = vec![];
+    for (ph_index, ph) in format.placeholders.iter().enumerate() {
+        let ty = match (ph_to_field_map[ph_index], equal_to[ph_index]) {
+            (Some(i), _) => fields[i].ty.clone(),
+            (None, Some(n)) if n < ph_index => ph_types[n].clone(),
+            (None, equal_to) => {
+                if equal_to.is_some() {
+                    let msg = "`{=N}` has to refer to an earlier placeholder";
+                    error.push(ph.src.error(msg)); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+                }
+                // literal placeholders have no type, but the types have to line up with the placeholders
+                Type::from_ty(syn::parse_quote! { () })
+            }
+        };
+        ph_types.push(ty);
+    }
 
-    let mut from_matches = vec![];
+    error.ok_or_build()?;
 
-    // types from placeholders have to be extracted in order, since they rely on the iterator
-    // => sort them by placeholder index, with defaults at the end
-    let n = fields.len();
-    fields.sort_by_key(|f| f.value_source.as_ref().map(|s| s.index_or(n)));
+    let regex_parts = RegexParts::with_equal_to(&format, &ph_types, &equal_to, false)?;
+    if let Some(take) = regex_parts.takes.first() {
+        let msg = "take option cannot be used in derive, because the format has to be matched by a single regex";
+        return format.placeholders[take.placeholder].src.err(msg); // checked in tests/fail/<channel>/invalid_take_option.rs
+    }
+    if let Some(error) = regex_parts.fancy.first() {
+        // the regex of a type is used in other regexes, which are built with `regex`
+        return Err(error.clone());
+    }
 
+    // values from placeholders have to be extracted in order, since they rely on the iterator
+    let values = regex_parts
+        .matcher_indices()
+        .into_iter()
+        .map(placeholder_value);
+    let matchers = &regex_parts.matchers;
+    // the format matched, but a `{=N}` placeholder differs
+    let checks = regex_parts.constraint_checks(placeholder_value, quote! { src.no_match(); });
+
+    let mut field_values = vec![];
     for field in fields {
         let ident = field.ident;
         let ty = field.ty;
 
-        let mut value = field.value_source.unwrap().get(&ty, &regex_parts.matchers);
+        let mut value = field.value_source.unwrap().get(&ty);
         // unwrap is safe because the unused_field_iter above ensures that all fields have a value_source
 
         if let Some(conv) = field.conversion {
             value = conv.apply(value, &ident);
         }
 
-        from_matches.push(quote! { #ident: #value });
+        field_values.push(quote! { #ident: #value });
     }
     error.ok_or_build()?;
 
-    let from_matches = quote! { { #(#from_matches),* } };
+    let from_matches = quote! {{
+        #( let #values = #matchers; )*
+        #checks
+        #path { #(#field_values),* }
+    }};
 
     Ok((regex_parts, from_matches, str_lifetimes))
 }
@@ -324,12 +392,23 @@ fn merge_lifetimes(
     (lifetime, lifetimed_generics)
 }
 
+/// The `REGEX_OPTIONS` of the `RegexRepresentation` implementation, if any were given
+fn regex_options_const(regex_options: &RegexOptions) -> Option<TokenStream> {
+    if regex_options.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        const REGEX_OPTIONS: ::sscanf::RegexOptions = #regex_options;
+    })
+}
+
 pub fn parse_struct(
     name: &syn::Ident,
     generics: &syn::Generics,
-    attrs: Vec<syn::Attribute>,
+    mut attrs: Vec<syn::Attribute>,
     data: syn::DataStruct,
 ) -> Result<TokenStream> {
+    let regex_options = RegexOptions::take_from_attrs(&mut attrs)?;
     let attr = StructAttribute::from_attrs(attrs)?
         .ok_or_else(|| {
             let mut msg = "FromScanf: structs must have a format string as an attribute.
@@ -341,15 +420,19 @@ Alternatively, you can use #[sscanf(transparent)] to derive FromScanf for a sing
             Error::new_spanned(name, msg) // checked in tests/fail/derive_struct_attributes.rs
         })?;
 
-    let (regex_parts, from_matches, str_lifetimes) = parse_format(attr, data.fields)?;
+    let (mut regex_parts, from_matches, str_lifetimes) =
+        parse_format(attr, data.fields, quote! { #name })?;
+    regex_options.enable_unicode(&mut regex_parts);
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let regex = regex_parts.regex();
+    let regex_options = regex_options_const(&regex_options);
     let regex_impl = quote! {
         #[automatically_derived]
         impl #impl_generics ::sscanf::RegexRepresentation for #name #ty_generics #where_clause {
-            const REGEX: &'static ::std::primitive::str = #regex;
+            const REGEX: &'static ::core::primitive::str = #regex;
+            #regex_options
         }
     };
 
@@ -362,12 +445,12 @@ Alternatively, you can use #[sscanf(transparent)] to derive FromScanf for a sing
         impl #impl_generics ::sscanf::FromScanf<#lifetime> for #name #ty_generics #where_clause {
             type Err = ::sscanf::errors::FromScanfFailedError;
             const NUM_CAPTURES: usize = #num_captures;
-            fn from_matches(src: &mut ::sscanf::regex::SubCaptureMatches<'_, #lifetime>) -> ::std::result::Result<Self, Self::Err> {
+            fn from_matches(src: &mut ::sscanf::Captures<#lifetime>) -> ::core::result::Result<Self, Self::Err> {
                 let start_len = src.len();
-                src.next().unwrap(); // skip the whole match
+                src.skip_captures(1, stringify!(#name)); // the whole match
 
-                let mut catcher = || -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
-                    ::std::result::Result::Ok(#name #from_matches)
+                let mut catcher = || -> ::core::result::Result<Self, ::sscanf::alloc::boxed::Box<dyn ::sscanf::ErrorTrait>> {
+                    ::core::result::Result::Ok(#from_matches)
                 };
                 let res = catcher().map_err(|error| ::sscanf::errors::FromScanfFailedError {
                     type_name: stringify!(#name),
@@ -375,10 +458,7 @@ Alternatively, you can use #[sscanf(transparent)] to derive FromScanf for a sing
                 })?;
                 let n = start_len - src.len();
                 if n != Self::NUM_CAPTURES {
-                    panic!(
-                        "sscanf: {}::NUM_CAPTURES = {} but {} were taken{}",
-                        stringify!(#name), Self::NUM_CAPTURES, n, ::sscanf::errors::WRONG_CAPTURES_HINT
-                    );
+                    src.invalid(stringify!(#name), Self::NUM_CAPTURES, n);
                 }
                 Ok(res)
             }
@@ -394,9 +474,10 @@ Alternatively, you can use #[sscanf(transparent)] to derive FromScanf for a sing
 pub fn parse_enum(
     name: &syn::Ident,
     generics: &syn::Generics,
-    attrs: Vec<syn::Attribute>,
+    mut attrs: Vec<syn::Attribute>,
     data: syn::DataEnum,
 ) -> Result<TokenStream> {
+    let regex_options = RegexOptions::take_from_attrs(&mut attrs)?;
     let attr = EnumAttribute::from_attrs(attrs)?;
     let autogen = attr.as_ref().and_then(|attr| match attr.kind {
         EnumAttributeKind::AutoGen(kind) => Some((kind, attr.src.clone())),
@@ -447,7 +528,7 @@ Use `#[sscanf(format = \"...\")]` to specify a format for a variant with fields
         let ident = variant.ident;
 
         let (variant_parts, from_matches, variant_str_lifetimes) =
-            parse_format(variant_attr, variant.fields)?;
+            parse_format(variant_attr, variant.fields, quote! { #name::#ident })?;
 
         let variant_num_captures_list = variant_parts.num_captures_list();
         let num_captures = quote! { #(#variant_num_captures_list)+* };
@@ -463,10 +544,10 @@ Use `#[sscanf(format = \"...\")]` to specify a format for a variant with fields
             let expected = #num_captures;
 
             remaining -= expected;
-            if src.next().expect(::sscanf::errors::EXPECT_NEXT_HINT).is_some() {
-                return ::std::result::Result::Ok(#name::#ident #from_matches);
-            } else if expected > 1 { // one was already taken by `src.next()` above
-                src.nth(expected - 2).expect(::sscanf::errors::EXPECT_NEXT_HINT);
+            if src.next_optional(stringify!(#name)).is_some() {
+                return ::core::result::Result::Ok(#from_matches);
+            } else { // one was already taken by `src.next_optional()` above
+                src.skip_captures(expected - 1, stringify!(#name));
             }
         };
         variant_constructors.push(matcher);
@@ -474,6 +555,7 @@ Use `#[sscanf(format = \"...\")]` to specify a format for a variant with fields
         regex_parts.push_literal(")");
     }
     regex_parts.push_literal(")");
+    regex_options.enable_unicode(&mut regex_parts);
 
     if variant_constructors.is_empty() {
         let msg = if autogen.is_some() {
@@ -488,10 +570,12 @@ To do this, add #[sscanf(format = \"...\")] to a variant"
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let regex = regex_parts.regex();
+    let regex_options = regex_options_const(&regex_options);
     let regex_impl = quote! {
         #[automatically_derived]
         impl #impl_generics ::sscanf::RegexRepresentation for #name #ty_generics #where_clause {
-            const REGEX: &'static ::std::primitive::str = #regex;
+            const REGEX: &'static ::core::primitive::str = #regex;
+            #regex_options
         }
     };
 
@@ -505,32 +589,28 @@ To do this, add #[sscanf(format = \"...\")] to a variant"
 
             const NUM_CAPTURES: usize = #(#num_captures_list)+*;
 
-            fn from_matches(src: &mut ::sscanf::regex::SubCaptureMatches<'_, #lifetime>) -> ::std::result::Result<Self, Self::Err> {
+            fn from_matches(src: &mut ::sscanf::Captures<#lifetime>) -> ::core::result::Result<Self, Self::Err> {
                 let start_len = src.len();
                 let mut remaining = Self::NUM_CAPTURES;
-                src.next().unwrap(); // skip the whole match
+                src.skip_captures(1, stringify!(#name)); // the whole match
                 remaining -= 1;
 
-                let mut catcher = || -> ::std::result::Result<Self, ::std::boxed::Box<dyn ::std::error::Error>> {
+                let mut catcher = || -> ::core::result::Result<Self, ::sscanf::alloc::boxed::Box<dyn ::sscanf::ErrorTrait>> {
                     #(#variant_constructors)*
 
-                    unreachable!("sscanf: enum regex matched but no variant was captured");
+                    // none of the variants participated in the match
+                    ::core::result::Result::Err(src.invalid(stringify!(#name), 1, 0).into())
                 };
                 let res = catcher().map_err(|error| ::sscanf::errors::FromScanfFailedError {
                     type_name: stringify!(#name),
                     error,
                 })?;
 
-                if remaining > 0 {
-                    src.nth(remaining - 1).expect(::sscanf::errors::EXPECT_NEXT_HINT);
-                }
+                src.skip_captures(remaining, stringify!(#name));
 
                 let n = start_len - src.len();
                 if n != Self::NUM_CAPTURES {
-                    panic!(
-                        "sscanf: {}::NUM_CAPTURES = {} but {} were taken{}",
-                        stringify!(#name), Self::NUM_CAPTURES, n, ::sscanf::errors::WRONG_CAPTURES_HINT
-                    );
+                    src.invalid(stringify!(#name), Self::NUM_CAPTURES, n);
                 }
                 Ok(res)
             }
@@ -552,3 +632,47 @@ pub fn parse_union(
     let msg = "FromScanf: unions not supported yet";
     Error::err_spanned(name, msg)
 }
+
+pub fn derive_radix(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    data: syn::Data,
+) -> Result<TokenStream> {
+    let msg = "ScanfRadix can only be derived for structs with exactly one field";
+    let field = match data {
+        syn::Data::Struct(data) if data.fields.len() == 1 => {
+            data.fields.into_iter().next().unwrap()
+        }
+        _ => return Error::err_spanned(name, msg), // checked in tests/fail/derive_radix.rs
+    };
+
+    let inner = &field.ty;
+    let constructor = match &field.ident {
+        Some(ident) => quote! { |value| #name { #ident: value } },
+        None => quote! { |value| #name(value) },
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // the body uses the span of the field, so that a missing implementation on the inner type
+    // is reported there
+    let span = inner.span();
+    let inner_trait = quote::quote_spanned! {span=> <#inner as ::sscanf::ScanfRadix> };
+    let body = quote::quote_spanned! {span=>
+        const BITS: u32 = #inner_trait::BITS;
+        const SIGNED: bool = #inner_trait::SIGNED;
+        type Err = #inner_trait::Err;
+        fn from_str_radix(src: &::core::primitive::str, radix: u32) -> ::core::result::Result<Self, Self::Err> {
+            #inner_trait::from_str_radix(src, radix).map(#constructor)
+        }
+        fn from_twos_complement_str_radix(src: &::core::primitive::str, radix: u32) -> ::core::result::Result<Self, Self::Err> {
+            #inner_trait::from_twos_complement_str_radix(src, radix).map(#constructor)
+        }
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::sscanf::ScanfRadix for #name #ty_generics #where_clause {
+            #body
+        }
+    })
+}
@@ -0,0 +1,125 @@
+use crate::*;
+
+/// A placeholder that can be matched without a regex
+enum DirectKind {
+    /// A primitive integer, which matches `[-+]?\d{1,max_digits}`
+    Int,
+    /// A `str`, `Cow<str>` or `String`, which matches `.+?`
+    Str,
+}
+
+impl DirectKind {
+    fn from_type(ty: &Type) -> Option<Self> {
+        if !matches!(ty.kind, TypeKind::Other) || ty.std_type() == Some("String") {
+            Some(Self::Str)
+        } else if ty.is_primitive_int() {
+            Some(Self::Int)
+        } else {
+            None
+        }
+    }
+}
+
+/// Code that matches the format without a regex, which evaluates to a
+/// `::sscanf::Scan<Result<(...), ::sscanf::errors::Error>>`.
+///
+/// This is only possible if the format consists of literal text and placeholders of primitive
+/// integers and strings without any options, and every placeholder except for the last one is
+/// followed by literal text that tells where it ends. Integers must not be followed by a digit.
+/// Types are recognized with [`Type::std_type`], so aliases of these types use the regex.
+pub fn direct_parser(
+    format: &FormatString,
+    types: &[Type],
+    escape_input: bool,
+) -> Option<TokenStream> {
+    if !escape_input {
+        return None;
+    }
+    let mut kinds = vec![];
+    for (i, (ph, ty)) in format.placeholders.iter().zip(types).enumerate() {
+        if ph.config.is_some()
+            || ph.alternatives().is_some()
+            || !matches!(ph.interpolation(), Ok(None))
+            || !matches!(ph.equal_to(), Ok(None))
+        {
+            return None;
+        }
+        let kind = DirectKind::from_type(ty)?;
+        let delimiter = &format.literals[i + 1];
+        let is_last = i + 1 == format.placeholders.len();
+        if delimiter.is_empty() && !is_last {
+            return None;
+        }
+        if let DirectKind::Int = kind {
+            if delimiter.chars().next().map_or(false, char::is_numeric) {
+                return None;
+            }
+        }
+        kinds.push(kind);
+    }
+
+    // the nested tuple of matched values is built from the back
+    let mut scan = quote! { ::sscanf::scan_end(rest) };
+    for (i, kind) in kinds.iter().enumerate().rev() {
+        let delimiter = &format.literals[i + 1];
+        let is_last = i + 1 == kinds.len();
+        scan = match kind {
+            DirectKind::Int => {
+                // the same limits as in the RegexRepresentation of the type, which allows 20
+                // digits for `usize` and `isize` on every target
+                let ty = types[i].inner();
+                let bits = match types[i].std_type() {
+                    Some("usize") | Some("isize") => quote! { 64 },
+                    _ => quote! { <#ty as ::sscanf::ScanfRadix>::BITS },
+                };
+                quote! {
+                    ::sscanf::scan_int(
+                        rest,
+                        <#ty as ::sscanf::ScanfRadix>::SIGNED,
+                        ::sscanf::radix_digits(#bits, 10),
+                        #delimiter,
+                    )
+                    .and_then(|(value, rest)| #scan.map(|next| (value, next)))
+                }
+            }
+            DirectKind::Str if is_last => quote! {
+                ::sscanf::scan_end_str(rest, #delimiter).map(|value| (value, ()))
+            },
+            DirectKind::Str => quote! {
+                ::sscanf::scan_str(rest, #delimiter, |rest| #scan)
+            },
+        };
+    }
+    let prefix = &format.literals[0];
+
+    let value = |i: usize| quote::format_ident!("value_{}", i);
+    let mut pattern = quote! { () };
+    for i in (0..kinds.len()).rev() {
+        let value = value(i);
+        pattern = quote! { (#value, #pattern) };
+    }
+    let converters = kinds.iter().zip(types).enumerate().map(|(i, (kind, ty))| {
+        let value = value(i);
+        match (kind, &ty.kind) {
+            (DirectKind::Str, TypeKind::Str(_)) => quote! { #value },
+            (DirectKind::Str, TypeKind::CowStr(_)) => {
+                quote! { ::sscanf::alloc::borrow::Cow::Borrowed(#value) }
+            }
+            _ => {
+                let ty = ty.inner();
+                quote! { ::sscanf::parse_str::<#ty>(#value)? }
+            }
+        }
+    });
+
+    Some(quote! {
+        ::sscanf::scan_literal(input, #prefix)
+            .and_then(|rest| #scan)
+            .map(|#pattern| {
+                let convert = || -> ::core::result::Result<_, ::sscanf::errors::Error> {
+                    ::core::result::Result::Ok( ( #(#converters),* ) )
+                };
+                convert()
+            })
+    })
+}
@@ -1,6 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use std::fmt::Display;
 
+#[derive(Clone)]
 pub struct Error(TokenStream);
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -21,6 +22,12 @@ impl Error {
     pub fn builder() -> ErrorBuilder {
         ErrorBuilder::new()
     }
+    /// Wraps the errors in a block, so that several of them can be emitted where an expression
+    /// is expected
+    pub fn in_block(self) -> Self {
+        let errors = self.0;
+        Error(quote::quote! {{ #errors }})
+    }
 }
 
 pub struct ErrorBuilder(TokenStream);
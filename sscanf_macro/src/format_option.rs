@@ -1,5 +1,7 @@
 use crate::*;
 
+pub const UNRECOGNIZED_OPTION: &str = "unrecognized format option.
+Hint: Regex format options must start and end with '/'";
 pub const MISSING_CLOSE_STRING: &str = "missing '}' to close a placeholder. If the '{' was intended to be a literal, escape it with '{{'";
 
 pub struct FormatOption<'a> {
@@ -8,9 +10,43 @@ pub struct FormatOption<'a> {
 }
 
 pub enum FormatOptionKind {
-    Radix { radix: u8, prefix: PrefixPolicy },
+    Radix {
+        radix: u8,
+        prefix: PrefixPolicy,
+        twos_complement: bool,
+    },
     Regex(String),
+    /// A custom regex that only `fancy-regex` supports, like one with lookaround
+    FancyRegex(String),
+    /// Path to a `&'static str` constant with a regex, like `@crate::patterns::IDENT`
+    RegexConst(syn::Path),
     Hashtag,
+    Float {
+        decimal_comma: bool,
+        precision: Option<u8>,
+        finite: bool,
+    },
+    /// A character class like `[a-z]`, already converted to regex syntax
+    CharSet(String),
+    /// Options for `str`, `String` and `Cow<str>`
+    Str {
+        empty: bool,
+        greedy: bool,
+        trim: bool,
+    },
+    /// Text with balanced brackets like `()`, optionally ignoring brackets in quotes
+    Balanced {
+        open: char,
+        close: char,
+        quoted: bool,
+    },
+    /// Takes as many bytes as the value of the placeholder with the given index
+    Take(usize),
+    /// One of several literal texts, like `(a|b)`
+    Alternatives(Vec<String>),
+    /// An option whose meaning depends on the type. For the types from std, `builtin_kind` turns it
+    /// into one of the kinds above. Other types get it passed to `FromScanfWithOptions`.
+    User(String),
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PrefixPolicy {
@@ -118,32 +154,126 @@ impl<'a> FormatOption<'a> {
 
                 let src = src.slice(start..=end);
 
-                match regex_syntax::Parser::new().parse(&regex) {
-                    Ok(hir) => {
-                        if contains_capture_group(&hir) {
-                            let msg = "custom regex cannot contain capture groups '(...)'.
-Either make them non-capturing by adding '?:' after the '(' or remove/escape the '(' and ')'";
-                            return src.err(msg);
-                        }
-                    }
-                    Err(err) => {
-                        let msg = format!("{}\n\nIn custom Regex format option", err);
-                        return src.err(&msg); // checked in tests/fail/<channel>/invalid_custom_regex.rs
+                if let Err(err) = regex_syntax::Parser::new().parse(&regex) {
+                    if let Some(fancy) = fancy_regex(&regex) {
+                        let kind =
+                            FormatOptionKind::FancyRegex(fancy.map_err(|msg| src.error(msg))?);
+                        return Ok((Self { src, kind }, close_bracket_index));
                     }
+                    let msg = format!(
+                        "{}\n\nIn custom Regex format option{}",
+                        err,
+                        fancy_hint(&err)
+                    );
+                    return src.err(&msg); // checked in tests/fail/<channel>/invalid_custom_regex.rs
                 }
 
-                let kind = FormatOptionKind::Regex(regex);
+                let kind = FormatOptionKind::Regex(non_capturing(&regex));
                 Ok((Self { src, kind }, close_bracket_index))
             }
             '}' => {
                 let msg = "format options cannot be empty. Consider removing the ':'";
                 src.slice(start..=start).err(msg) // checked in tests/fail/<channel>/invalid_placeholder.rs
             }
-            _ => Self::from_radix(input, src, start, outer_start),
+            '[' => Self::from_char_set(input, src, start, outer_start),
+            '@' => Self::from_regex_const(input, src, start, outer_start),
+            _ => Self::from_text(input, src, start, outer_start),
+        }
+    }
+
+    fn from_char_set<I: Iterator<Item = (usize, char)>>(
+        input: &mut std::iter::Peekable<I>,
+        src: &StrLitSlice<'a>,
+        start: usize,
+        outer_start: usize,
+    ) -> Result<(Self, usize)> {
+        let (close_bracket_index, _) = input
+            .find(|(_, c)| *c == '}')
+            .ok_or_else(|| src.slice(outer_start..).error(MISSING_CLOSE_STRING))?; // checked in tests/fail/<channel>/invalid_placeholder.rs
+
+        let src = src.slice(start..close_bracket_index);
+        let text = src.text();
+        // `[]` is not a valid character class, so it can be used for balanced brackets
+        if let Some(kind) = Self::parse_balanced(&src, text)? {
+            return Ok((Self { src, kind }, close_bracket_index));
+        }
+        if !text.ends_with(']') {
+            let msg = "character set option has to end with ']'";
+            return src.err(msg); // checked in tests/fail/<channel>/invalid_char_set.rs
+        }
+
+        let class = if src.is_raw() {
+            text.to_string()
+        } else {
+            // same as in regex options: `\\` is a single backslash, everything else is passed on
+            let mut class = String::new();
+            let mut chars = text.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    class.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some(c @ ('\\' | '"' | '\'')) => class.push(c),
+                    Some(c) => {
+                        class.push('\\');
+                        class.push(c);
+                    }
+                    None => class.push('\\'),
+                }
+            }
+            class
+        };
+
+        use regex_syntax::hir::HirKind;
+        match regex_syntax::Parser::new().parse(&class) {
+            Ok(hir) if matches!(hir.kind(), HirKind::Class(_) | HirKind::Literal(_)) => {}
+            Ok(_) => {
+                let msg = "character set option has to be a single character class like `[a-z]`";
+                return src.err(msg); // checked in tests/fail/<channel>/invalid_char_set.rs
+            }
+            Err(err) => {
+                let msg = format!("{}\n\nIn character set format option", err);
+                return src.err(&msg); // checked in tests/fail/<channel>/invalid_char_set.rs
+            }
         }
+
+        let kind = FormatOptionKind::CharSet(class);
+        Ok((Self { src, kind }, close_bracket_index))
+    }
+
+    fn from_regex_const<I: Iterator<Item = (usize, char)>>(
+        input: &mut std::iter::Peekable<I>,
+        src: &StrLitSlice<'a>,
+        start: usize,
+        outer_start: usize,
+    ) -> Result<(Self, usize)> {
+        let (close_bracket_index, _) = input
+            .find(|(_, c)| *c == '}')
+            .ok_or_else(|| src.slice(outer_start..).error(MISSING_CLOSE_STRING))?; // checked in tests/fail/<channel>/invalid_placeholder.rs
+
+        let src = src.slice(start..close_bracket_index);
+        let path = src.text()[1..]
+            .parse::<TokenStream>()
+            .ok()
+            .and_then(|tokens| syn::parse2::<syn::Path>(tokens.with_span(src.span())).ok());
+        let path = match path {
+            Some(path) => path,
+            None => {
+                let msg = "'@' has to be followed by the path of a `&str` constant with a regex";
+                return src.err(msg); // checked in tests/fail/<channel>/invalid_regex_const.rs
+            }
+        };
+
+        // capture groups in the constant can only be detected once the regex is built
+        let kind = FormatOptionKind::RegexConst(path);
+        Ok((Self { src, kind }, close_bracket_index))
     }
 
-    fn from_radix<I: Iterator<Item = (usize, char)>>(
+    /// Parses the options that are the same for every type: radix options, `take=N`, balanced
+    /// brackets and alternatives. Everything else depends on the type and is returned as
+    /// `FormatOptionKind::User`, see `builtin_kind`.
+    fn from_text<I: Iterator<Item = (usize, char)>>(
         input: &mut std::iter::Peekable<I>,
         src: &StrLitSlice<'a>,
         start: usize,
@@ -155,7 +285,18 @@ Either make them non-capturing by adding '?:' after the '(' or remove/escape the
 
         let src = src.slice(start..close_bracket_index);
 
-        let (radix, prefix) = match src.text() {
+        // a trailing '~' reads the digits as the two's complement bit pattern of the type
+        let (text, twos_complement) = match src.text().strip_suffix('~') {
+            Some(text) => (text, true),
+            None => (src.text(), false),
+        };
+        let twos_complement_error = || {
+            let msg = "two's complement modifier '~' can only be used with radix options";
+            src.err(msg) // checked in tests/fail/<channel>/invalid_radix_option.rs
+        };
+
+        let (radix, prefix) = match text {
+            "#" if twos_complement => return twos_complement_error(),
             "#" => {
                 let kind = FormatOptionKind::Hashtag;
                 return Ok((Self { src, kind }, close_bracket_index));
@@ -172,7 +313,10 @@ Either make them non-capturing by adding '?:' after the '(' or remove/escape the
                     return src.err(msg); // checked in tests/fail/<channel>/invalid_radix_option.rs
                 }
 
-                if let Some(n) = s.strip_prefix('r') {
+                let radix_digits = s
+                    .strip_prefix('r')
+                    .filter(|n| n.chars().all(|c| c.is_ascii_digit()));
+                if let Some(n) = radix_digits {
                     let radix = n.parse::<u8>().map_err(|_| {
                         let msg = "radix option 'r' has to be followed by a number";
                         src.error(msg) // checked in tests/fail/<channel>/invalid_radix_option.rs
@@ -183,35 +327,327 @@ Either make them non-capturing by adding '?:' after the '(' or remove/escape the
                         return src.err(msg); // checked in tests/fail/<channel>/invalid_radix_option.rs
                     }
                     (radix, PrefixPolicy::Never)
+                } else if let Some(index) = s.strip_prefix("take=") {
+                    let index = index.parse::<usize>().map_err(|_| {
+                        let msg = "take option has to be followed by the index of a placeholder";
+                        src.error(msg) // checked in tests/fail/<channel>/invalid_take_option.rs
+                    })?;
+                    if twos_complement {
+                        return twos_complement_error();
+                    }
+                    let kind = FormatOptionKind::Take(index);
+                    return Ok((Self { src, kind }, close_bracket_index));
+                } else if let Some(kind) = Self::parse_balanced(&src, s)? {
+                    if twos_complement {
+                        return twos_complement_error();
+                    }
+                    return Ok((Self { src, kind }, close_bracket_index));
+                } else if let Some(alternatives) = parse_alternatives(s) {
+                    if twos_complement {
+                        return twos_complement_error();
+                    }
+                    let kind = FormatOptionKind::Alternatives(alternatives);
+                    return Ok((Self { src, kind }, close_bracket_index));
                 } else {
-                    let msg = "unrecognized format option.
-Hint: Regex format options must start and end with '/'";
-                    return src.err(msg); // checked in tests/fail/<channel>/raw_string.rs
+                    let kind = FormatOptionKind::User(unescape_option(&src));
+                    return Ok((Self { src, kind }, close_bracket_index));
                 }
             }
         };
 
-        let kind = FormatOptionKind::Radix { radix, prefix };
+        let kind = FormatOptionKind::Radix {
+            radix,
+            prefix,
+            twos_complement,
+        };
         Ok((Self { src, kind }, close_bracket_index))
     }
+
+    /// The meaning of a `FormatOptionKind::User` option for the types from std, which have the
+    /// string, float and character set options built in. Other types get the option as written.
+    pub fn builtin_kind(&self) -> Result<FormatOptionKind> {
+        let src = &self.src;
+        let text = src.text();
+        if text.ends_with('~') {
+            let msg = "two's complement modifier '~' can only be used with radix options";
+            return src.err(msg); // checked in tests/fail/<channel>/invalid_radix_option.rs
+        }
+        if text.starts_with(',') || text.starts_with('.') || text.starts_with("finite") {
+            Self::parse_float(src)
+        } else if let Some(kind) = Self::parse_str(src, text)? {
+            Ok(kind)
+        } else if is_user_option(text) {
+            Ok(FormatOptionKind::User(text.to_string()))
+        } else {
+            src.err(UNRECOGNIZED_OPTION) // checked in tests/fail/<channel>/raw_string.rs
+        }
+    }
+
+    /// Parses string options, which can be any comma-separated combination of:
+    /// - `*` or `empty`: allow empty matches
+    /// - `greedy`: match as much as possible
+    /// - `trim`: remove surrounding whitespace
+    ///
+    /// Returns `None` if `text` contains anything else.
+    fn parse_str(src: &StrLitSlice<'a>, text: &str) -> Result<Option<FormatOptionKind>> {
+        const NAMES: &[&str] = &["*", "empty", "greedy", "trim"];
+        if !text.split(',').all(|part| NAMES.contains(&part)) {
+            return Ok(None);
+        }
+
+        let mut empty = false;
+        let mut greedy = false;
+        let mut trim = false;
+        for part in text.split(',') {
+            let option = match part {
+                "*" | "empty" => &mut empty,
+                "greedy" => &mut greedy,
+                _ => &mut trim,
+            };
+            if std::mem::replace(option, true) {
+                let msg = format!("string option '{}' was specified multiple times", part);
+                return src.err(&msg); // checked in tests/fail/<channel>/invalid_str_option.rs
+            }
+        }
+        Ok(Some(FormatOptionKind::Str {
+            empty,
+            greedy,
+            trim,
+        }))
+    }
+
+    /// Parses balanced bracket options: `()`, `[]` or `<>`, optionally followed by `,quoted`
+    ///
+    /// Returns `None` if `text` does not start with one of the bracket pairs.
+    fn parse_balanced(src: &StrLitSlice<'a>, text: &str) -> Result<Option<FormatOptionKind>> {
+        let (open, close, rest) = if let Some(rest) = text.strip_prefix("()") {
+            ('(', ')', rest)
+        } else if let Some(rest) = text.strip_prefix("[]") {
+            ('[', ']', rest)
+        } else if let Some(rest) = text.strip_prefix("<>") {
+            ('<', '>', rest)
+        } else {
+            return Ok(None);
+        };
+        let quoted = match rest {
+            "" => false,
+            ",quoted" => true,
+            _ => {
+                let msg = "balanced bracket options can only be followed by ',quoted'";
+                return src.err(msg); // checked in tests/fail/<channel>/invalid_balanced_option.rs
+            }
+        };
+        Ok(Some(FormatOptionKind::Balanced {
+            open,
+            close,
+            quoted,
+        }))
+    }
+
+    /// Parses float options, which can be any combination of:
+    /// - `,`: decimal comma instead of a decimal point
+    /// - `.N`: exactly `N` fractional digits
+    /// - `finite`: don't accept `inf`, `infinity` or `nan`
+    fn parse_float(src: &StrLitSlice<'a>) -> Result<FormatOptionKind> {
+        let mut decimal_comma = false;
+        let mut precision = None;
+        let mut finite = false;
+
+        let mut rest = src.text();
+        while !rest.is_empty() {
+            let (name, duplicate) = if let Some(r) = rest.strip_prefix("finite") {
+                rest = r;
+                ("finite", std::mem::replace(&mut finite, true))
+            } else if let Some(r) = rest.strip_prefix(',') {
+                rest = r;
+                (",", std::mem::replace(&mut decimal_comma, true))
+            } else if let Some(r) = rest.strip_prefix('.') {
+                let end = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+                let n = r[..end].parse::<u8>().map_err(|_| {
+                    let msg =
+                        "precision option '.' has to be followed by a number between 0 and 255";
+                    src.error(msg) // checked in tests/fail/<channel>/invalid_float_option.rs
+                })?;
+                rest = &r[end..];
+                (".", precision.replace(n).is_some())
+            } else {
+                let msg = "unrecognized float format option.
+Hint: Float options are any combination of ',', '.<precision>' and 'finite'";
+                return src.err(msg); // checked in tests/fail/<channel>/invalid_float_option.rs
+            };
+            if duplicate {
+                let msg = format!("float option '{}' was specified multiple times", name);
+                return src.err(&msg); // checked in tests/fail/<channel>/invalid_float_option.rs
+            }
+        }
+
+        Ok(FormatOptionKind::Float {
+            decimal_comma,
+            precision,
+            finite,
+        })
+    }
+}
+
+/// Literal alternatives like `(a|b)` or `(|s)`, which need at least one `|`
+pub fn parse_alternatives(text: &str) -> Option<Vec<String>> {
+    let inner = text.strip_prefix('(')?.strip_suffix(')')?;
+    if !inner.contains('|') {
+        return None;
+    }
+    Some(inner.split('|').map(String::from).collect())
+}
+
+/// Whether `regex-lite` can build the regex, which only supports a subset of the syntax and no
+/// Unicode classes like `\p{L}`
+#[cfg(feature = "lite")]
+pub fn lite_supports(regex: &str) -> bool {
+    regex_lite::Regex::new(regex).is_ok()
+}
+#[cfg(not(feature = "lite"))]
+pub fn lite_supports(_regex: &str) -> bool {
+    false
+}
+
+const FANCY_HINT: &str =
+    ".\nLookaround is only supported with the `fancy` feature of sscanf, which uses `fancy-regex`";
+
+/// Points to the `fancy` feature if the regex failed because of lookaround
+fn fancy_hint(err: &regex_syntax::Error) -> &'static str {
+    use regex_syntax::ast::ErrorKind;
+    match err {
+        regex_syntax::Error::Parse(err)
+            if cfg!(not(feature = "fancy")) && *err.kind() == ErrorKind::UnsupportedLookAround =>
+        {
+            FANCY_HINT
+        }
+        _ => "",
+    }
+}
+
+/// Error for backreferences like `\1` in a custom regex that needs `fancy-regex`
+#[cfg(feature = "fancy")]
+const NO_BACKREFERENCES: &str =
+    "backreferences are not supported in custom regexes, because all groups are made non-capturing";
+
+/// A regex that `regex-syntax` rejected, but `fancy-regex` accepts. Returns the regex with all
+/// groups made non-capturing, like `non_capturing` does for regular custom regexes.
+///
+/// Backreferences are rejected, because they would refer to the groups that are made non-capturing.
+#[cfg(feature = "fancy")]
+fn fancy_regex(regex: &str) -> Option<std::result::Result<String, &'static str>> {
+    fancy_regex::Regex::new(regex).ok()?;
+
+    let mut ret = String::with_capacity(regex.len());
+    let mut chars = regex.chars().peekable();
+    let mut class_depth = 0;
+    while let Some(c) = chars.next() {
+        ret.push(c);
+        match c {
+            '\\' => {
+                let next = chars.next();
+                if class_depth == 0 && matches!(next, Some('1'..='9' | 'k')) {
+                    return Some(Err(NO_BACKREFERENCES));
+                }
+                ret.extend(next);
+            }
+            '[' => {
+                class_depth += 1;
+                if class_depth == 1 {
+                    // a `]` at the start of a class is a literal
+                    if chars.peek() == Some(&'^') {
+                        ret.extend(chars.next());
+                    }
+                    if chars.peek() == Some(&']') {
+                        ret.extend(chars.next());
+                    }
+                }
+            }
+            ']' if class_depth > 0 => class_depth -= 1,
+            '(' if class_depth == 0 => {
+                let rest = chars.clone().take(3).collect::<String>();
+                if rest == "?P=" {
+                    return Some(Err(NO_BACKREFERENCES));
+                } else if !rest.starts_with('?') {
+                    ret.push_str("?:");
+                } else if rest.starts_with("?P<")
+                    || (rest.starts_with("?<") && rest != "?<=" && rest != "?<!")
+                {
+                    // named group: skip the name
+                    chars.by_ref().find(|c| *c == '>');
+                    ret.push_str("?:");
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(Ok(ret))
+}
+#[cfg(not(feature = "fancy"))]
+fn fancy_regex(_regex: &str) -> Option<std::result::Result<String, &'static str>> {
+    None
+}
+
+/// The built-in options that are not a combination of string or float options are plain words
+/// like `q` or `alpha`
+pub fn is_user_option(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// The text of an option for `FromScanfWithOptions`, with the escapes of a non-raw string resolved,
+/// so that it is equal to the name in `OPTIONS`
+fn unescape_option(src: &StrLitSlice) -> String {
+    let text = src.text();
+    if src.is_raw() || !text.contains('\\') {
+        return text.to_string();
+    }
+    syn::parse_str::<syn::LitStr>(&format!("\"{}\"", text))
+        .map(|lit| lit.value())
+        .unwrap_or_else(|_| text.to_string())
 }
 
-fn contains_capture_group(hir: &regex_syntax::hir::Hir) -> bool {
-    use regex_syntax::hir::HirKind::*;
-    match hir.kind() {
-        Group(g) => {
-            if g.kind != regex_syntax::hir::GroupKind::NonCapturing {
-                return true;
+/// Makes all capture groups in a custom regex non-capturing, because the captures of the generated
+/// regex are counted by position
+fn non_capturing(regex: &str) -> String {
+    use regex_syntax::ast::{Ast, Flags, GroupKind};
+    fn rewrite(ast: &mut Ast) -> bool {
+        // every branch has to be rewritten, so this can't stop at the first change
+        let rewrite_all = |asts: &mut Vec<Ast>| {
+            let mut changed = false;
+            for ast in asts {
+                changed |= rewrite(ast);
             }
-            contains_capture_group(g.hir.as_ref())
+            changed
+        };
+        match ast {
+            Ast::Group(group) => {
+                let capturing = !matches!(group.kind, GroupKind::NonCapturing(_));
+                if capturing {
+                    let span = group.span;
+                    group.kind = GroupKind::NonCapturing(Flags {
+                        span,
+                        items: vec![],
+                    });
+                }
+                rewrite(&mut group.ast) || capturing
+            }
+            Ast::Repetition(repetition) => rewrite(&mut repetition.ast),
+            Ast::Alternation(alternation) => rewrite_all(&mut alternation.asts),
+            Ast::Concat(concat) => rewrite_all(&mut concat.asts),
+            _ => false,
         }
-        Concat(c) | Alternation(c) => c.iter().any(contains_capture_group),
-        Repetition(r) => contains_capture_group(r.hir.as_ref()),
-        _ => false,
-        // regex-syntax 0.7+ version
-        // Capture(_) => true,
-        // Concat(c) | Alternation(c) => c.iter().any(contains_capture_group),
-        // Repetition(r) => contains_capture_group(r.sub.as_ref()),
-        // _ => false,
+    }
+
+    let mut ast = regex_syntax::ast::parse::Parser::new()
+        .parse(regex)
+        .expect("regex was already validated");
+    if rewrite(&mut ast) {
+        ast.to_string()
+    } else {
+        regex.to_string() // keep the original text if there is nothing to change
     }
 }
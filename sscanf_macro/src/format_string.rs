@@ -2,7 +2,8 @@ use crate::*;
 
 pub struct FormatString<'a> {
     pub placeholders: Vec<Placeholder<'a>>,
-    pub parts: Vec<String>, // contains placeholders.len() + 1 escaped parts
+    pub parts: Vec<String>,    // contains placeholders.len() + 1 escaped parts
+    pub literals: Vec<String>, // the unescaped text of the parts, without the placeholder brackets
 }
 
 impl<'a> FormatString<'a> {
@@ -10,6 +11,8 @@ impl<'a> FormatString<'a> {
         let mut placeholders = vec![];
         let mut parts = vec![];
         let mut current_part = String::new();
+        let mut literals = vec![];
+        let mut current_literal = String::new();
 
         // keep the iterator as a variable to allow peeking and advancing in a sub-function
         let mut iter = src.text().char_indices().peekable();
@@ -23,6 +26,7 @@ impl<'a> FormatString<'a> {
                     current_part.push('(');
                     parts.push(current_part);
                     current_part = String::from(")");
+                    literals.push(std::mem::take(&mut current_literal));
                     continue;
                 }
             } else if c == '}' {
@@ -39,12 +43,15 @@ impl<'a> FormatString<'a> {
             }
 
             current_part.push(c);
+            current_literal.push(c);
         }
 
         parts.push(current_part);
+        literals.push(current_literal);
         Ok(Self {
             placeholders,
             parts,
+            literals,
         })
     }
 }
@@ -10,20 +10,24 @@ pub(crate) use syn::{
 };
 
 mod attribute;
+mod direct;
 mod error;
 mod format_option;
 mod format_string;
 mod placeholder;
+mod regex_options;
 mod regex_parts;
 mod str_lit;
 mod ty;
 mod utils;
 
 pub(crate) use attribute::*;
+pub(crate) use direct::*;
 pub(crate) use error::*;
 pub(crate) use format_option::*;
 pub(crate) use format_string::*;
 pub(crate) use placeholder::*;
+pub(crate) use regex_options::*;
 pub(crate) use regex_parts::*;
 pub(crate) use str_lit::*;
 pub(crate) use ty::*;
@@ -37,6 +41,8 @@ struct ScanfInner {
     fmt: StrLit,
     /// Types after the format string
     type_tokens: Vec<Type<'static>>,
+    /// Options for the regex after a `;`
+    regex_options: RegexOptions,
 }
 /// Input string, format string and types for `sscanf` and `sscanf_unescaped`
 struct Scanf {
@@ -46,6 +52,14 @@ struct Scanf {
     inner: ScanfInner,
 }
 
+/// Buffer, input string, format string and types for `sscanf_with`
+struct ScanfWith {
+    /// the `ScanBuffer` that is reused
+    buffer: syn::Expr,
+    /// input, format string and types
+    scanf: Scanf,
+}
+
 impl Parse for ScanfInner {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         if input.is_empty() {
@@ -55,18 +69,34 @@ impl Parse for ScanfInner {
 
         let fmt = input.parse::<StrLit>()?;
 
-        let type_tokens = if input.is_empty() {
-            vec![]
-        } else {
+        let mut type_tokens = vec![];
+        if !input.is_empty() && !input.peek(Token![;]) {
             input.parse::<Token![,]>()?; // the comma after the format string
 
-            input
-                .parse_terminated(Type::parse, Token![,])?
-                .into_iter()
-                .collect()
-        };
+            while !input.is_empty() && !input.peek(Token![;]) {
+                type_tokens.push(input.parse()?);
+                if input.is_empty() || input.peek(Token![;]) {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let mut regex_options = RegexOptions::default();
+        if !input.is_empty() {
+            let semicolon = input.parse::<Token![;]>()?;
+            if input.is_empty() {
+                let msg = "expected regex options after `;`, like `size_limit = 50_000_000`";
+                return Err(syn::Error::new_spanned(semicolon, msg)); // checked in tests/fail/regex_options.rs
+            }
+            regex_options = input.parse()?;
+        }
 
-        Ok(ScanfInner { fmt, type_tokens })
+        Ok(ScanfInner {
+            fmt,
+            type_tokens,
+            regex_options,
+        })
     }
 }
 impl Parse for Scanf {
@@ -106,28 +136,68 @@ impl Parse for Scanf {
     }
 }
 
+impl Parse for ScanfWith {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // see the comments in `Scanf::parse` for why this is necessary
+        if input.is_empty() {
+            let msg = "at least 3 Parameters required: Buffer, input and format string";
+            return Err(syn::Error::new(Span::call_site(), msg)); // checked in tests/fail/missing_params.rs
+        }
+        let buffer = input.parse()?;
+        if input.is_empty() {
+            let msg = "at least 3 Parameters required: Missing input and format string";
+            return Err(syn::Error::new_spanned(buffer, msg)); // checked in tests/fail/missing_params.rs
+        }
+        let comma = input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            let msg = "at least 3 Parameters required: Missing input and format string";
+            return Err(syn::Error::new_spanned(comma, msg)); // checked in tests/fail/missing_params.rs
+        }
+        let scanf = input.parse()?;
+
+        Ok(ScanfWith { buffer, scanf })
+    }
+}
+
 #[proc_macro]
 pub fn sscanf(input: TokenStream1) -> TokenStream1 {
     let input = syn::parse_macro_input!(input as Scanf);
-    sscanf_internal(input, true)
+    sscanf_internal(input, true, None)
 }
 
 #[proc_macro]
 pub fn sscanf_unescaped(input: TokenStream1) -> TokenStream1 {
     let input = syn::parse_macro_input!(input as Scanf);
-    sscanf_internal(input, false)
+    sscanf_internal(input, false, None)
+}
+
+#[proc_macro]
+pub fn sscanf_with(input: TokenStream1) -> TokenStream1 {
+    let input = syn::parse_macro_input!(input as ScanfWith);
+    sscanf_internal(input.scanf, true, Some(input.buffer))
 }
 
 #[proc_macro]
 pub fn sscanf_get_regex(input: TokenStream1) -> TokenStream1 {
     let input = syn::parse_macro_input!(input as ScanfInner);
-    let (regex, _) = match generate_regex(&input, true) {
+    let regex_parts = match generate_regex(&input, true, true) {
         Ok(v) => v,
         Err(e) => return e.into(),
     };
+    // the type of the returned regex doesn't depend on the `lite` feature
+    let engine = Engine::new(&regex_parts, &input.regex_options, false);
+    let regex = declare_regex(
+        &quote::format_ident!("REGEX"),
+        &regex_parts.regex_builder,
+        &input.regex_options,
+        engine,
+    );
     let ret = quote! {{
         #regex
-        &REGEX
+        match ::core::result::Result::as_ref(&REGEX) {
+            ::core::result::Result::Ok(regex) => regex,
+            ::core::result::Result::Err(error) => ::core::panic!("sscanf: Cannot generate Regex: {}", error),
+        }
     }};
     ret.into()
 }
@@ -153,11 +223,35 @@ pub fn derive_from_sscanf(input: TokenStream1) -> TokenStream1 {
     }
 }
 
-fn sscanf_internal(input: Scanf, escape_input: bool) -> TokenStream1 {
-    let (regex, matcher) = match generate_regex(&input.inner, escape_input) {
+#[proc_macro_derive(ScanfRadix)]
+pub fn derive_scanf_radix(input: TokenStream1) -> TokenStream1 {
+    let syn::DeriveInput {
+        ident,
+        generics,
+        data,
+        ..
+    } = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match derive::derive_radix(&ident, &generics, data) {
+        Ok(res) => res.into(),
+        Err(err) => err.into(),
+    }
+}
+
+fn sscanf_internal(input: Scanf, escape_input: bool, buffer: Option<syn::Expr>) -> TokenStream1 {
+    let (format, types) = match parse_format(&input.inner, escape_input) {
+        Ok(v) => v,
+        Err(e) => return e.into(),
+    };
+    let mut regex_parts = match RegexParts::new(&format, &types, true) {
         Ok(v) => v,
         Err(e) => return e.into(),
     };
+    if let Err(e) = input.inner.regex_options.check_fancy(&regex_parts) {
+        return e.in_block().into();
+    }
+    input.inner.regex_options.enable_unicode(&mut regex_parts);
+    let format_text = input.inner.fmt.to_slice().text().to_string();
     let src_str = {
         let src_str = input.src_str;
         let span = FullSpan::from_spanned(&src_str);
@@ -165,37 +259,524 @@ fn sscanf_internal(input: Scanf, escape_input: bool) -> TokenStream1 {
 
         // wrapping the input in a manual call to str::get ensures that the user
         // gets an appropriate error message if they try to use a non-string input
-        quote! { ::std::primitive::str::get(#param, ..).unwrap() }
+        quote! { ::core::primitive::str::get(#param, ..).unwrap() }
+    };
+    let use_buffer = buffer.is_some();
+    let buffer = buffer.map(|buffer| {
+        quote! {
+            #[allow(unused_variables)] // regexes with `{=expr}` can't use the buffer
+            let buffer: &mut ::sscanf::ScanBuffer = #buffer;
+        }
+    });
+    if !regex_parts.takes.is_empty() {
+        return sscanf_staged(
+            &regex_parts,
+            src_str,
+            buffer,
+            &input.inner.regex_options,
+            &format_text,
+        )
+        .into();
+    }
+
+    let engine = Engine::new(&regex_parts, &input.inner.regex_options, true);
+    let name = quote::format_ident!("REGEX");
+    let regex = declare_regex(
+        &name,
+        &regex_parts.regex_builder,
+        &input.inner.regex_options,
+        engine,
+    );
+    let matcher = &regex_parts.matchers;
+    let result = if regex_parts.constraints.is_empty() {
+        quote! { res }
+    } else {
+        let value = |i: usize| quote::format_ident!("value_{}", i);
+        let values = regex_parts.matcher_indices().into_iter().map(value);
+        let checks = regex_parts.constraint_checks(
+            value,
+            quote! { return ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed); },
+        );
+        let output = regex_parts.output_indices().map(value);
+        quote! {
+            res.and_then(|( #(#values),* )| {
+                #checks
+                ::core::result::Result::Ok( ( #(#output),* ) )
+            })
+        }
+    };
+    let body = quote! {
+        src.next().unwrap(); // skip the whole match
+
+        let mut matcher = || -> ::core::result::Result<_, ::sscanf::alloc::boxed::Box<dyn ::sscanf::ErrorTrait>> {
+            ::core::result::Result::Ok( ( #(#matcher),* ) )
+        };
+        let res = matcher().map_err(|e| ::sscanf::errors::Error::ParsingFailed(e));
+
+        if let ::core::option::Option::Some(error) = src.take_error() {
+            return ::core::result::Result::Err(error);
+        }
+        if res.is_ok() && src.len() != 0 {
+            return ::core::result::Result::Err(::sscanf::errors::Error::invalid_regex_representation(
+                #format_text, NUM_CAPTURES, NUM_CAPTURES - src.len()
+            ));
+        }
+        #result
+    };
+    let regex_match = match_regex(
+        &name,
+        &quote! { input },
+        body,
+        use_buffer && is_static(&regex_parts.regex_builder),
+        &regex_parts.num_captures(),
+        &format_text,
+        engine,
+    );
+    let direct = if input.inner.regex_options.is_empty() {
+        direct_parser(&format, &types, escape_input)
+    } else {
+        None // the options might change what the regex matches
+    };
+    let matcher = match direct {
+        // simple formats are matched without the regex, which is only built if that is not possible
+        Some(direct) => quote! {
+            match #direct {
+                ::sscanf::Scan::Match(res) => res,
+                ::sscanf::Scan::NoMatch => ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed),
+                ::sscanf::Scan::Unsure => #regex_match,
+            }
+        },
+        None => regex_match,
     };
     let ret = quote! {{
         #regex
         #[allow(clippy::needless_borrow)]
         let input: &str = #src_str;
+        #buffer
+        #[allow(clippy::needless_question_mark)]
+        #matcher
+    }};
+    ret.into()
+}
+
+/// Code for a format with `take` options or balanced brackets. Every part between two of those
+/// placeholders is matched by its own regex, starting where the previous part ended.
+fn sscanf_staged(
+    regex_parts: &RegexParts,
+    src_str: TokenStream,
+    buffer: Option<TokenStream>,
+    regex_options: &RegexOptions,
+    format_text: &str,
+) -> TokenStream {
+    let value = |i: usize| quote::format_ident!("value_{}", i);
+    let engine = Engine::new(regex_parts, regex_options, true);
+
+    let mut regexes = vec![];
+    let mut stages = vec![];
+    let mut matcher_indices = regex_parts.matcher_indices().into_iter();
+    for (i, stage) in regex_parts.stages().into_iter().enumerate() {
+        let name = quote::format_ident!("REGEX_{}", i);
+        regexes.push(declare_regex(
+            &name,
+            stage.regex_builder,
+            regex_options,
+            engine,
+        ));
+
+        let matchers = stage.matchers;
+        let values = matcher_indices
+            .by_ref()
+            .take(matchers.len())
+            .map(value)
+            .collect::<Vec<_>>();
+        // every stage starts with a `^`, so the whole match ends after its length
+        let advance = stage
+            .take
+            .map(|_| quote! { rest = &rest[whole_match.len()..]; });
+        let body = quote! {
+            let whole_match = src.next().unwrap().unwrap();
+            #advance
+
+            let mut matcher = || -> ::core::result::Result<_, ::sscanf::alloc::boxed::Box<dyn ::sscanf::ErrorTrait>> {
+                ::core::result::Result::Ok( ( #(#matchers,)* ) )
+            };
+            let res = matcher().map_err(|e| ::sscanf::errors::Error::ParsingFailed(e));
+
+            if let ::core::option::Option::Some(error) = src.take_error() {
+                return ::core::result::Result::Err(error);
+            }
+            let res = res?;
+            if src.len() != 0 {
+                return ::core::result::Result::Err(::sscanf::errors::Error::invalid_regex_representation(
+                    #format_text, NUM_CAPTURES, NUM_CAPTURES - src.len()
+                ));
+            }
+            ::core::result::Result::Ok(res)
+        };
+        let regex_match = match_regex(
+            &name,
+            &quote! { rest },
+            body,
+            buffer.is_some() && is_static(stage.regex_builder),
+            &stage.num_captures,
+            format_text,
+            engine,
+        );
+        let code = quote! {
+            let ( #(#values,)* ) = #regex_match?;
+        };
+        stages.push((code, values, stage.take));
+    }
+
+    // everything after balanced brackets is matched again for every possible end of the brackets,
+    // so the code is built from the back
+    let mut tail = quote! {};
+    let mut tail_values = vec![];
+    let mut tail_advances = false;
+    for (code, mut values, take) in stages.into_iter().rev() {
+        let take_code = take.map(|take| {
+            let taken_value = value(take.placeholder);
+            let converter = &take.converter;
+            let taken = match &take.kind {
+                TakeKind::Length {
+                    length_of,
+                    length_ty,
+                } => {
+                    let length = value(*length_of);
+                    quote! {
+                        let length = <::core::primitive::usize as ::core::convert::TryFrom<#length_ty>>::try_from(#length)
+                            .map_err(|e| ::sscanf::errors::Error::ParsingFailed(::sscanf::alloc::boxed::Box::new(e)))?;
+                        let taken = rest.get(..length).ok_or(::sscanf::errors::Error::MatchFailed)?;
+                        rest = &rest[length..];
+                        let #taken_value = #converter;
+                        #tail
+                    }
+                }
+                TakeKind::Balanced {
+                    open,
+                    close,
+                    quoted,
+                } => {
+                    let rest = if tail_advances {
+                        quote! { mut rest }
+                    } else {
+                        quote! { rest }
+                    };
+                    quote! {
+                        let (taken, ( #(#tail_values,)* )) = ::sscanf::match_balanced(rest, #open, #close, #quoted, |#rest| -> ::core::result::Result<_, ::sscanf::errors::Error> {
+                            #tail
+                            ::core::result::Result::Ok( ( #(#tail_values,)* ) )
+                        })?;
+                        let #taken_value = #converter;
+                    }
+                }
+            };
+            values.push(taken_value);
+            taken
+        });
+        tail = quote! {
+            #code
+            #take_code
+        };
+        values.append(&mut tail_values);
+        tail_values = values;
+        tail_advances = take.is_some();
+    }
+    let checks = regex_parts.constraint_checks(
+        value,
+        quote! { return ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed); },
+    );
+    let values = regex_parts.output_indices().map(value);
+
+    quote! {{
+        #(#regexes)*
+        #[allow(clippy::needless_borrow)]
+        let input: &str = #src_str;
+        #buffer
         #[allow(clippy::needless_question_mark)]
-        REGEX.captures(input)
-            .ok_or_else(|| ::sscanf::errors::Error::MatchFailed)
-            .and_then(|cap| {
-                let mut src = cap.iter();
+        let mut matcher = || -> ::core::result::Result<_, ::sscanf::errors::Error> {
+            let mut rest = input;
+            #tail
+            #checks
+            ::core::result::Result::Ok( ( #(#values),* ) )
+        };
+        matcher()
+    }}
+}
+
+/// Code that matches the regex `name` against `input` and evaluates `body` with the captures in
+/// `src`, or returns `Error::MatchFailed`. `body` has to evaluate to a `Result<_, sscanf::Error>`.
+///
+/// If the regex doesn't have `num_captures` capture groups, `Error::InvalidRegexRepresentation` is
+/// returned for the format string `format_text` instead.
+///
+/// If `use_buffer` is set, the capture locations are taken from the `ScanBuffer` in `buffer`
+/// instead of allocating new ones. This is only possible for regexes in a `static`.
+/// Regexes of `fancy-regex` never use the buffer.
+fn match_regex(
+    name: &syn::Ident,
+    input: &TokenStream,
+    body: TokenStream,
+    use_buffer: bool,
+    num_captures: &TokenStream,
+    format_text: &str,
+    engine: Engine,
+) -> TokenStream {
+    let regex = quote::format_ident!("regex");
+    let matcher = match engine {
+        Engine::Fancy => match_fancy_captures(&regex, input, body, format_text),
+        _ => match_regex_captures(&regex, input, body, use_buffer, engine),
+    };
+    quote! {{
+        const NUM_CAPTURES: ::core::primitive::usize = #num_captures;
+
+        match ::core::result::Result::as_ref(&#name) {
+            ::core::result::Result::Err(error) => ::core::result::Result::Err(
+                ::sscanf::errors::Error::invalid_regex(#format_text, error)
+            ),
+            ::core::result::Result::Ok(#regex) if #regex.captures_len() != NUM_CAPTURES => {
+                ::core::result::Result::Err(::sscanf::errors::Error::invalid_regex_representation(
+                    #format_text, NUM_CAPTURES, #regex.captures_len()
+                ))
+            }
+            ::core::result::Result::Ok(#regex) => #matcher,
+        }
+    }}
+}
+
+/// See `match_regex`
+fn match_regex_captures(
+    name: &syn::Ident,
+    input: &TokenStream,
+    body: TokenStream,
+    use_buffer: bool,
+    engine: Engine,
+) -> TokenStream {
+    let lite = engine == Engine::Lite;
+    if !use_buffer {
+        let from_captures = if lite {
+            quote! { from_lite }
+        } else {
+            quote! { from_regex }
+        };
+        return quote! {
+            match #name.captures(#input) {
+                ::core::option::Option::Some(cap) => {
+                    let mut src = ::sscanf::Captures::#from_captures(cap);
+                    let src = &mut src;
+                    let mut body = || -> ::core::result::Result<_, ::sscanf::errors::Error> {
+                        #body
+                    };
+                    body()
+                }
+                ::core::option::Option::None => ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed),
+            }
+        };
+    }
+    // the locations of regex-lite are kept separately in the buffer
+    let (take, from_locations, into_locations, restore) = if lite {
+        (
+            quote! { take_lite_locations },
+            quote! { from_lite_locations },
+            quote! { into_lite_locations },
+            quote! { restore_lite_locations },
+        )
+    } else {
+        (
+            quote! { take_locations },
+            quote! { from_locations },
+            quote! { into_locations },
+            quote! { restore_locations },
+        )
+    };
+    quote! {{
+        let mut locations = buffer.#take(#name);
+        if #name.captures_read(&mut locations, #input).is_some() {
+            let mut src = ::sscanf::Captures::#from_locations(#input, locations);
+            let mut body = || -> ::core::result::Result<_, ::sscanf::errors::Error> {
                 let src = &mut src;
-                src.next().unwrap(); // skip the whole match
+                #body
+            };
+            let res = body();
+            if let ::core::option::Option::Some(locations) = src.#into_locations() {
+                buffer.#restore(#name, locations);
+            }
+            res
+        } else {
+            buffer.#restore(#name, locations);
+            ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed)
+        }
+    }}
+}
 
-                let mut matcher = || -> ::std::result::Result<_, ::std::boxed::Box<dyn ::std::error::Error>> {
-                    ::std::result::Result::Ok( ( #(#matcher),* ) )
+/// See `match_regex`. Matching with `fancy-regex` can fail at runtime, like when its backtrack
+/// limit is exceeded, which is reported as `Error::InvalidRegex`
+fn match_fancy_captures(
+    name: &syn::Ident,
+    input: &TokenStream,
+    body: TokenStream,
+    format_text: &str,
+) -> TokenStream {
+    quote! {
+        match #name.captures(#input) {
+            ::core::result::Result::Ok(::core::option::Option::Some(cap)) => {
+                let mut src = ::sscanf::Captures::from_fancy(cap);
+                let src = &mut src;
+                let mut body = || -> ::core::result::Result<_, ::sscanf::errors::Error> {
+                    #body
                 };
-                let res = matcher().map_err(|e| ::sscanf::errors::Error::ParsingFailed(e));
+                body()
+            }
+            ::core::result::Result::Ok(::core::option::Option::None) => {
+                ::core::result::Result::Err(::sscanf::errors::Error::MatchFailed)
+            }
+            ::core::result::Result::Err(error) => ::core::result::Result::Err(
+                ::sscanf::errors::Error::invalid_regex(#format_text, &error)
+            ),
+        }
+    }
+}
+
+/// Whether a regex can be built once and stored in a `static`
+fn is_static(regex_builder: &[RegexPart]) -> bool {
+    !regex_builder
+        .iter()
+        .any(|part| matches!(part, RegexPart::Interpolated(_)))
+}
+
+/// The regex engine that a format is built with
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Engine {
+    Regex,
+    /// `regex-lite` with the `lite` feature, unless the format uses something it doesn't support
+    Lite,
+    /// `fancy-regex` for custom regexes with lookaround
+    Fancy,
+}
+
+impl Engine {
+    fn new(regex_parts: &RegexParts, options: &RegexOptions, allow_lite: bool) -> Self {
+        if !regex_parts.fancy.is_empty() {
+            Engine::Fancy
+        } else if allow_lite
+            && cfg!(feature = "lite")
+            && options.lite_supports()
+            && regex_parts
+                .regex_builder
+                .iter()
+                .all(RegexPart::lite_supports)
+        {
+            Engine::Lite
+        } else {
+            Engine::Regex
+        }
+    }
+
+    /// The path of the crate, which has a `Regex`, an `Error` and an `escape` function
+    fn krate(self) -> TokenStream {
+        match self {
+            Engine::Regex => quote! { ::sscanf::regex },
+            Engine::Lite => quote! { ::sscanf::regex_lite },
+            Engine::Fancy => quote! { ::sscanf::fancy_regex },
+        }
+    }
+
+    /// Code that builds the regex in `regex_str`. `fancy-regex` doesn't take any options.
+    fn build(self, options: &RegexOptions, regex_str: TokenStream) -> TokenStream {
+        match self {
+            Engine::Regex => quote! { #options.build(#regex_str) },
+            Engine::Lite => quote! { #options.build_lite(#regex_str) },
+            Engine::Fancy => quote! { ::sscanf::fancy_regex::Regex::new(#regex_str) },
+        }
+    }
+}
 
-                if res.is_ok() && src.len() != 0 {
-                    panic!("sscanf: {} captures generated, but {} were taken",
-                        REGEX.captures_len(), REGEX.captures_len() - src.len()
-                    );
+/// Declaration of a regex named `name`, which holds the `Result` of building the regex.
+///
+/// Regexes are usually created once in a `lazy_regex!`, but `{=expr}` placeholders are only known
+/// at runtime, so those regexes are built on every call.
+fn declare_regex(
+    name: &syn::Ident,
+    regex_builder: &[RegexPart],
+    options: &RegexOptions,
+    engine: Engine,
+) -> TokenStream {
+    let krate = engine.krate();
+    if is_static(regex_builder) {
+        let build = engine.build(options, quote! { regex_str });
+        return quote! {
+            ::sscanf::lazy_regex! {
+                #name: ::core::result::Result<#krate::Regex, #krate::Error> = {
+                    let regex_str = ::sscanf::const_format::concatcp!( #(#regex_builder),* );
+                    #build
                 }
-                res
-            })
-    }};
-    ret.into()
+            }
+        };
+    }
+
+    // everything between the interpolations can still be concatenated at compile time
+    let mut pieces = vec![];
+    for group in regex_builder.split(|part| matches!(part, RegexPart::Interpolated(_))) {
+        pieces.push(quote! { ::sscanf::const_format::concatcp!( #(#group),* ) });
+    }
+    let mut interpolations = regex_builder.iter().filter_map(|part| match part {
+        RegexPart::Interpolated(expr) => Some(quote::quote_spanned! {expr.span()=>
+            &*#krate::escape(&::sscanf::alloc::string::ToString::to_string(&(#expr)))
+        }),
+        _ => None,
+    });
+    let build = engine.build(options, quote! { &regex_str });
+    let mut builder = vec![pieces[0].clone()];
+    for piece in &pieces[1..] {
+        builder.push(interpolations.next().unwrap());
+        builder.push(piece.clone());
+    }
+
+    quote! {
+        #[allow(non_snake_case)]
+        let #name = {
+            let regex_str: ::sscanf::alloc::string::String = [ #(#builder),* ].concat();
+            #build
+        };
+    }
+}
+
+/// `static_regex` is set if the format has to be matched by a single regex that is built at
+/// compile time, which excludes `take` options and `{=expr}` placeholders
+fn generate_regex(
+    input: &ScanfInner,
+    escape_input: bool,
+    static_regex: bool,
+) -> Result<RegexParts> {
+    let (format, types) = parse_format(input, escape_input)?;
+
+    if static_regex {
+        for ph in &format.placeholders {
+            if ph.interpolation()?.is_some() {
+                let msg = "`{=expr}` placeholders cannot be used here, because the regex has to be built at compile time";
+                return ph.src.err(msg); // checked in tests/fail/<channel>/invalid_interpolation.rs
+            }
+            if let Some(FormatOptionKind::Take(_)) = ph.config.as_ref().map(|c| &c.kind) {
+                let msg = "take option cannot be used here, because the format has to be matched by a single regex";
+                return ph.config.as_ref().unwrap().src.err(msg); // checked in tests/fail/<channel>/invalid_take_option.rs
+            }
+        }
+    }
+
+    let mut regex_parts = RegexParts::new(&format, &types, false)?;
+    input
+        .regex_options
+        .check_fancy(&regex_parts)
+        .map_err(Error::in_block)?;
+    input.regex_options.enable_unicode(&mut regex_parts);
+    Ok(regex_parts)
 }
 
-fn generate_regex(input: &ScanfInner, escape_input: bool) -> Result<(TokenStream, Vec<Matcher>)> {
+/// Parses the format string and finds the type of every placeholder
+fn parse_format(
+    input: &ScanfInner,
+    escape_input: bool,
+) -> Result<(FormatString<'_>, Vec<Type<'_>>)> {
     let mut format = FormatString::new(input.fmt.to_slice(), escape_input)?;
     format.parts[0].insert(0, '^');
     format.parts.last_mut().unwrap().push('$');
@@ -237,10 +818,25 @@ fn generate_regex(input: &ScanfInner, escape_input: bool) -> Result<(TokenStream
 
     let mut ph_index = 0;
     let mut visited = vec![false; input.type_tokens.len()];
-    let mut types = vec![];
+    let mut types: Vec<Type> = vec![];
     let mut error = Error::builder();
 
-    for ph in &format.placeholders {
+    for (i, ph) in format.placeholders.iter().enumerate() {
+        if ph.interpolation()?.is_some() || ph.alternatives().is_some() {
+            // literal placeholders have no type, but the types have to line up with the placeholders
+            types.push(Type::from_ty(syn::parse_quote! { () }));
+            continue;
+        }
+        if let Some(equal_to) = ph.equal_to()? {
+            if equal_to >= i {
+                let msg = "`{=N}` has to refer to an earlier placeholder";
+                error.push(ph.src.error(msg)); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+            } else if types.len() == i {
+                // otherwise, there was an error in an earlier placeholder
+                types.push(types[equal_to].clone());
+            }
+            continue;
+        }
         match find_ph_type(ph, &mut visited, &mut ph_index, &input.type_tokens) {
             Ok(ty) => types.push(ty),
             Err(e) => error.push(e),
@@ -255,27 +851,5 @@ fn generate_regex(input: &ScanfInner, escape_input: bool) -> Result<(TokenStream
 
     error.ok_or_build()?;
 
-    let regex_parts = RegexParts::new(&format, &types)?;
-
-    let regex = regex_parts.regex();
-    let num_captures = regex_parts.num_captures();
-    let regex = quote! { ::sscanf::lazy_static::lazy_static! {
-        static ref REGEX: ::sscanf::regex::Regex = {
-            let regex_str = #regex;
-            let regex = ::sscanf::regex::Regex::new(regex_str)
-                .expect("sscanf: Cannot generate Regex");
-
-            const NUM_CAPTURES: ::std::primitive::usize = #num_captures;
-
-            if regex.captures_len() != NUM_CAPTURES {
-                panic!(
-                    "sscanf: Regex has {} capture groups, but {} were expected.{}",
-                    regex.captures_len(), NUM_CAPTURES, ::sscanf::errors::WRONG_CAPTURES_HINT
-                );
-            }
-            regex
-        };
-    }};
-
-    Ok((regex, regex_parts.matchers))
+    Ok((format, types))
 }
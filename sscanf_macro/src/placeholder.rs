@@ -64,4 +64,49 @@ impl<'a> Placeholder<'a> {
 
         Ok(Placeholder { src, ident, config })
     }
+
+    /// The index `N` of a `{=N}` placeholder, which has to be equal to placeholder `N`
+    pub fn equal_to(&self) -> Result<Option<usize>> {
+        let (ident, n) = match self.interpolation_text() {
+            Some((ident, n)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => {
+                (ident, n)
+            }
+            _ => return Ok(None),
+        };
+        n.parse().map(Some).map_err(|_| {
+            let msg = "`{=N}` has to contain the index of an earlier placeholder";
+            ident.error(msg) // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+        })
+    }
+
+    /// The expression of a `{=expr}` placeholder, which inserts the value of `expr` as literal text
+    pub fn interpolation(&self) -> Result<Option<syn::Expr>> {
+        let (ident, text) = match self.interpolation_text() {
+            Some((_, n)) if !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()) => {
+                return Ok(None)
+            }
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        let error = |err: syn::Error| {
+            let msg = format!("invalid expression in `{{=expr}}` placeholder: {}", err);
+            ident.error(msg) // checked in tests/fail/<channel>/invalid_interpolation.rs
+        };
+        let tokens = text
+            .parse::<TokenStream>()
+            .map_err(|err| error(err.into()))?
+            .with_span(ident.span());
+        syn::parse2::<syn::Expr>(tokens).map(Some).map_err(error)
+    }
+
+    /// The alternatives of a `{(a|b)}` placeholder, which matches one of them as literal text
+    pub fn alternatives(&self) -> Option<Vec<String>> {
+        parse_alternatives(self.ident.as_ref()?.text())
+    }
+
+    /// The text after the `=` of a `{=...}` placeholder
+    fn interpolation_text(&self) -> Option<(&StrLitSlice<'a>, &str)> {
+        let ident = self.ident.as_ref()?;
+        ident.text().strip_prefix('=').map(|text| (ident, text))
+    }
 }
@@ -0,0 +1,185 @@
+use crate::*;
+
+use proc_macro2::{Delimiter, TokenTree};
+
+/// The methods of `sscanf::RegexOptions`, which map onto `regex::RegexBuilder`
+const OPTIONS: &[&str] = &["size_limit", "dfa_size_limit", "nest_limit", "unicode"];
+/// Options that `regex_lite::RegexBuilder` doesn't have, so formats with them are built with `regex`
+const NOT_IN_LITE: &[&str] = &["dfa_size_limit", "unicode"];
+
+/// Options for building the regex, like `size_limit = 50_000_000, unicode = false`
+#[derive(Default)]
+pub struct RegexOptions {
+    options: Vec<(syn::Ident, syn::Expr)>,
+}
+
+impl Parse for RegexOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Vec::<(syn::Ident, syn::Expr)>::new();
+        while !input.is_empty() {
+            let name = input.parse::<syn::Ident>()?;
+            let name_str = name.to_string();
+            if !OPTIONS.contains(&name_str.as_str()) {
+                let msg = if let Some(similar) = find_closest(&name_str, OPTIONS) {
+                    format!(
+                        "unknown regex option `{}`. Did you mean `{}`?",
+                        name_str, similar
+                    )
+                } else {
+                    let valid = list_items(OPTIONS, |option| format!("`{}`", option));
+                    format!(
+                        "unknown regex option `{}`. Valid options are: {}",
+                        name_str, valid
+                    )
+                };
+                return Err(syn::Error::new_spanned(name, msg)); // checked in tests/fail/regex_options.rs
+            }
+            if let Some((previous, _)) = options.iter().find(|(other, _)| *other == name) {
+                let msg = format!("regex option `{}` is specified multiple times", name);
+                let mut error = syn::Error::new_spanned(&name, msg);
+                error.combine(syn::Error::new_spanned(previous, "previous use here"));
+                return Err(error); // checked in tests/fail/regex_options.rs
+            }
+
+            if !input.peek(Token![=]) {
+                let msg = format!("expected `= <value>` after regex option `{}`", name);
+                return Err(syn::Error::new_spanned(name, msg)); // checked in tests/fail/regex_options.rs
+            }
+            let eq_sign = input.parse::<Token![=]>()?;
+            if input.is_empty() || input.peek(Token![,]) {
+                let msg = "expected a value after `=`";
+                return Err(syn::Error::new_spanned(eq_sign, msg)); // checked in tests/fail/regex_options.rs
+            }
+            let value = input.parse::<syn::Expr>()?;
+            options.push((name, value));
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self { options })
+    }
+}
+
+impl RegexOptions {
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// Whether `regex-lite` has all of these options
+    pub fn lite_supports(&self) -> bool {
+        self.options
+            .iter()
+            .all(|(name, _)| !NOT_IN_LITE.iter().any(|option| name == option))
+    }
+
+    /// Rejects custom regexes that need `fancy-regex`, which can't be built with these options
+    pub fn check_fancy(&self, regex_parts: &RegexParts) -> Result<()> {
+        let name = match self.options.first() {
+            Some((name, _)) if !regex_parts.fancy.is_empty() => name,
+            _ => return Ok(()),
+        };
+        let mut error = Error::builder();
+        for ph_error in &regex_parts.fancy {
+            error.push(ph_error.clone());
+        }
+        error
+            .with_spanned(name, "regex options are used here")
+            .build_err()
+    }
+
+    /// Puts the placeholders that can match non-ASCII text, like `{str}` or `{char}`, into a
+    /// `(?u:...)` group if the options contain `unicode = false`. Without Unicode mode, `.` would
+    /// match single bytes, which `regex` refuses to build for a `Regex` on `str`.
+    pub fn enable_unicode(&self, regex_parts: &mut RegexParts) {
+        let disabled = self.options.iter().any(|(name, value)| {
+            name == "unicode"
+                && matches!(value, syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Bool(b), .. }) if !b.value)
+        });
+        if !disabled {
+            return;
+        }
+        for part in &mut regex_parts.regex_builder {
+            if needs_unicode(part) {
+                let inner = std::mem::replace(part, RegexPart::Literal(String::new()));
+                *part = RegexPart::Unicode(Box::new(inner));
+            }
+        }
+    }
+
+    /// Removes `regex_options(...)` from the `#[sscanf(...)]` attributes of a derive, so that the
+    /// remaining attributes can be parsed as usual
+    pub fn take_from_attrs(attrs: &mut [syn::Attribute]) -> Result<Self> {
+        const NAME: &str = "regex_options";
+
+        let mut ret = None::<(TokenStream, Self)>;
+        for attr in attrs.iter_mut() {
+            if !attr.path().is_ident("sscanf") {
+                continue;
+            }
+            let list = match &mut attr.meta {
+                syn::Meta::List(list) => list,
+                _ => continue, // reported when parsing the other attributes
+            };
+
+            let tokens = list.tokens.clone().into_iter().collect::<Vec<_>>();
+            let mut rest = vec![];
+            let mut at_item_start = true;
+            let mut i = 0;
+            while i < tokens.len() {
+                let name = match &tokens[i] {
+                    TokenTree::Ident(ident) if at_item_start && ident == NAME => ident,
+                    token => {
+                        at_item_start = matches!(token, TokenTree::Punct(p) if p.as_char() == ',');
+                        rest.push(token.clone());
+                        i += 1;
+                        continue;
+                    }
+                };
+                let group = match tokens.get(i + 1) {
+                    Some(TokenTree::Group(group))
+                        if group.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        group
+                    }
+                    _ => {
+                        let msg = format!(
+                            "attribute `{0}` has the format: `#[sscanf({0}(<option> = <value>, ...))]`",
+                            NAME
+                        );
+                        return Error::err_spanned(name, msg); // checked in tests/fail/regex_options.rs
+                    }
+                };
+                let src = quote! { #name #group };
+                let options = syn::parse2::<Self>(group.stream())?;
+                if let Some((previous, _)) = &ret {
+                    let msg = format!("attribute `{}` is specified multiple times", NAME);
+                    return Error::builder()
+                        .with_spanned(src, msg)
+                        .with_spanned(previous, "previous use here")
+                        .build_err(); // checked in tests/fail/regex_options.rs
+                }
+                ret = Some((src, options));
+
+                i += 2;
+                if let Some(TokenTree::Punct(p)) = tokens.get(i) {
+                    if p.as_char() == ',' {
+                        i += 1;
+                    }
+                }
+            }
+            list.tokens = rest.into_iter().collect();
+        }
+        Ok(ret.map(|(_, options)| options).unwrap_or_default())
+    }
+}
+
+impl ToTokens for RegexOptions {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let calls = self.options.iter().map(|(name, value)| {
+            quote::quote_spanned! {name.span()=> .#name(#value) }
+        });
+        tokens.extend(quote! { ::sscanf::RegexOptions::new() #(#calls)* });
+    }
+}
@@ -8,6 +8,13 @@ use crate::*;
 pub enum NumCaptures {
     One,
     FromType(syn::Type, FullSpan),
+    WithOptions(syn::Type, FullSpan),
+    Sum(Vec<NumCaptures>),
+    /// The capture groups in the regex constant of a `{:@path}` option
+    RegexConst(syn::Path),
+    /// The capture groups in the `RegexRepresentation` of a type that are not taken by its
+    /// `FromScanf` implementation
+    Extra(syn::Type, FullSpan),
 }
 
 impl ToTokens for NumCaptures {
@@ -39,6 +46,21 @@ impl ToTokens for NumCaptures {
                     quote! { ::sscanf::FromScanf >::NUM_CAPTURES },
                 ));
             }
+            NumCaptures::WithOptions(ty, span) => {
+                // Same as above, but with `::sscanf::FromScanfWithOptions`
+                tokens.extend(span.apply_start(quote! { < }));
+                ty.to_tokens(tokens);
+                tokens.extend(span.apply(
+                    quote! { as },
+                    quote! { ::sscanf::FromScanfWithOptions >::NUM_CAPTURES },
+                ));
+            }
+            NumCaptures::Sum(parts) if parts.is_empty() => tokens.extend(quote! { 0 }),
+            NumCaptures::Sum(parts) => tokens.extend(quote! { (#(#parts)+*) }),
+            NumCaptures::RegexConst(path) => {
+                tokens.extend(quote! { ::sscanf::capture_groups(#path) })
+            }
+            NumCaptures::Extra(ty, span) => tokens.extend(extra_captures(ty, *span)),
         }
     }
 }
@@ -48,6 +70,36 @@ pub enum RegexPart {
     Literal(String),
     FromType(syn::Type, FullSpan),
     Custom(String),
+    /// A `&'static str` constant with a regex from a `{:@path}` option
+    Const(syn::Path),
+    /// Regex of a user-defined option from `FromScanfWithOptions::OPTIONS`
+    WithOptions(syn::Type, FullSpan, String),
+    /// Regex for a radix option, which depends on the number of bits and the signedness of the
+    /// `ScanfRadix` implementation of the type
+    Radix {
+        ty: syn::Type,
+        span: FullSpan,
+        prefix: String,
+        digits: String,
+        radix: u32,
+        twos_complement: bool,
+    },
+    /// The value of a `{=expr}` placeholder, which is only known at runtime
+    Interpolated(syn::Expr),
+    /// A part that can match non-ASCII text in a `(?u:...)` group, so that it still works with
+    /// `unicode = false`
+    Unicode(Box<RegexPart>),
+}
+
+impl RegexPart {
+    /// Whether `regex-lite` can build this part. The regex of a type is only known at runtime.
+    pub fn lite_supports(&self) -> bool {
+        match self {
+            RegexPart::Custom(regex) => lite_supports(regex),
+            RegexPart::Unicode(part) => part.lite_supports(),
+            _ => true,
+        }
+    }
 }
 
 impl ToTokens for RegexPart {
@@ -68,6 +120,46 @@ impl ToTokens for RegexPart {
                 ));
             }
             RegexPart::Custom(custom) => tokens.extend(quote! { #custom }),
+            // the type is checked by `::sscanf::capture_groups` in `NumCaptures::RegexConst`
+            RegexPart::Const(path) => tokens.extend(quote! { #path }),
+            RegexPart::WithOptions(ty, span, option) => {
+                // See the comment in `NumCaptures::FromType` for an explanation of the span
+                // Final expression: `<#ty as ::sscanf::FromScanfWithOptions>::OPTIONS`
+                let mut options = span.apply_start(quote! { < });
+                ty.to_tokens(&mut options);
+                options.extend(span.apply(
+                    quote! { as },
+                    quote! { ::sscanf::FromScanfWithOptions >::OPTIONS },
+                ));
+                tokens.extend(quote! { ::sscanf::option_regex(#options, #option) });
+            }
+            RegexPart::Radix {
+                ty,
+                span,
+                prefix,
+                digits,
+                radix,
+                twos_complement,
+            } => {
+                // the parts are inserted as separate arguments into the surrounding `concatcp!`
+                let sign = if *twos_complement {
+                    // the sign is part of the bit pattern
+                    quote! { "" }
+                } else {
+                    let signed = radix_trait_member(ty, *span, quote! { SIGNED });
+                    quote! { if #signed { "[-+]?" } else { "\\+?" } }
+                };
+                let bits = radix_trait_member(ty, *span, quote! { BITS });
+                tokens.extend(quote! {
+                    "(?i:", #sign, #prefix, "[", #digits, "]{1,",
+                    ::sscanf::radix_digits(#bits, #radix), "})"
+                });
+            }
+            RegexPart::Interpolated(_) => {
+                unreachable!("regexes with interpolations are built at runtime")
+            }
+            // the parts are inserted as separate arguments into the surrounding `concatcp!`
+            RegexPart::Unicode(part) => tokens.extend(quote! { "(?u:", #part, ")" }),
         }
     }
 }
@@ -82,20 +174,11 @@ pub enum Converter {
 impl ToTokens for Converter {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
-            Converter::Str => tokens.extend(quote! {
-                src.next()
-                   .expect(::sscanf::errors::EXPECT_NEXT_HINT)
-                   .expect(::sscanf::errors::EXPECT_CAPTURE_HINT)
-                   .as_str()
-            }),
-            Converter::CowStr => tokens.extend(quote! {
-                ::std::borrow::Cow::Borrowed(
-                    src.next()
-                        .expect(::sscanf::errors::EXPECT_NEXT_HINT)
-                        .expect(::sscanf::errors::EXPECT_CAPTURE_HINT)
-                        .as_str()
-                )
-            }),
+            Converter::Str => tokens.extend(next_capture_str()),
+            Converter::CowStr => {
+                let input = next_capture_str();
+                tokens.extend(quote! { ::sscanf::alloc::borrow::Cow::Borrowed(#input) })
+            }
             Converter::FromType(ty, span) => {
                 let call = span.apply(
                     quote! { ::sscanf::FromScanf },
@@ -113,6 +196,42 @@ impl ToTokens for Converter {
     }
 }
 
+/// Code for the number of capture groups in the regex of `ty` that are not taken by its
+/// `FromScanf` implementation
+fn extra_captures(ty: &syn::Type, span: FullSpan) -> TokenStream {
+    // See the comment in `NumCaptures::FromType` for an explanation of the span
+    let mut num_captures = span.apply_start(quote! { < });
+    ty.to_tokens(&mut num_captures);
+    num_captures.extend(span.apply(
+        quote! { as },
+        quote! { ::sscanf::FromScanf >::NUM_CAPTURES },
+    ));
+    let regex = RegexPart::FromType(ty.clone(), span);
+    quote! { ::sscanf::extra_captures(#num_captures, #regex) }
+}
+
+/// Captures and converter for a type that is matched by its own `RegexRepresentation`, where
+/// capture groups that are not taken by `FromScanf` have to be skipped
+fn from_type_with_regex(ty: &syn::Type, span: FullSpan) -> (NumCaptures, Converter) {
+    let num_captures = NumCaptures::Sum(vec![
+        NumCaptures::FromType(ty.clone(), span),
+        NumCaptures::Extra(ty.clone(), span),
+    ]);
+    let converter = Converter::FromType(ty.clone(), span);
+    let extra = extra_captures(ty, span);
+    let converter = Converter::Custom(quote! {{
+        let value = #converter;
+        (&mut *src).take(#extra).for_each(drop);
+        value
+    }});
+    (num_captures, converter)
+}
+
+/// Code to take the next capture from `src` as a `&str`
+fn next_capture_str() -> TokenStream {
+    quote! { src.next_str("str") }
+}
+
 pub struct Matcher {
     pub ty: syn::Type,
     pub num_captures: NumCaptures,
@@ -136,10 +255,7 @@ impl ToTokens for Matcher {
                     let n = start_len - src.len();
                     let expected = #num_captures;
                     if n != expected {
-                        panic!(
-                            "sscanf: {}::NUM_CAPTURES = {} but {} were taken{}",
-                            stringify!(#ty), expected, n, ::sscanf::errors::WRONG_CAPTURES_HINT
-                        );
+                        src.invalid(stringify!(#ty), expected, n);
                     }
                 }
                 value
@@ -148,9 +264,64 @@ impl ToTokens for Matcher {
     }
 }
 
+/// A placeholder whose text is found by hand instead of by the regex. The regex is split at this
+/// point, and the part after the placeholder is matched from where the placeholder ended.
+pub struct Take {
+    /// index of the placeholder in the format string
+    pub placeholder: usize,
+    pub kind: TakeKind,
+    /// index in `regex_builder` where the regex after this placeholder starts
+    pub builder_index: usize,
+    /// conversion from the taken `&str` named `taken` to the type of the placeholder
+    pub converter: TokenStream,
+}
+
+#[allow(clippy::large_enum_variant)] // don't care
+pub enum TakeKind {
+    /// `take=N`: as many bytes as the value of placeholder `length_of`
+    Length {
+        length_of: usize,
+        /// type of the length placeholder
+        length_ty: syn::Type,
+    },
+    /// balanced brackets like `{:()}`, which a regex can only match up to a fixed nesting depth
+    Balanced {
+        open: char,
+        close: char,
+        quoted: bool,
+    },
+}
+
+/// Part of a format with `take` options, which is matched by its own regex
+pub struct Stage<'a> {
+    pub regex_builder: &'a [RegexPart],
+    pub num_captures: TokenStream,
+    pub matchers: &'a [Matcher],
+    /// the take placeholder after this stage, or `None` for the last stage
+    pub take: Option<&'a Take>,
+}
+
+/// A `{=N}` placeholder, which has to be equal to placeholder `N` and is not part of the output
+pub struct Constraint {
+    /// index of the placeholder in the format string
+    pub placeholder: usize,
+    /// index of the placeholder that it has to be equal to
+    pub equal_to: usize,
+    /// span of the type, for errors about a missing `PartialEq` implementation
+    pub span: FullSpan,
+}
+
 pub struct RegexParts {
     pub regex_builder: Vec<RegexPart>,
     pub matchers: Vec<Matcher>,
+    pub takes: Vec<Take>,
+    pub constraints: Vec<Constraint>,
+    /// indices of the `{=expr}` and `{(a|b)}` placeholders, which only match literal text and
+    /// have no value
+    pub literals: Vec<usize>,
+    /// errors for the custom regexes that only `fancy-regex` supports, which are reported if the
+    /// regex can't be built with `fancy-regex`
+    pub fancy: Vec<Error>,
 }
 
 impl RegexParts {
@@ -158,6 +329,10 @@ impl RegexParts {
         Self {
             regex_builder: vec![],
             matchers: vec![],
+            takes: vec![],
+            constraints: vec![],
+            literals: vec![],
+            fancy: vec![],
         }
     }
 
@@ -165,36 +340,281 @@ impl RegexParts {
         self.regex_builder.push(RegexPart::Literal(literal.into()));
     }
 
-    pub fn new(format: &FormatString, type_sources: &[Type]) -> Result<Self> {
+    /// With `staged`, balanced bracket placeholders are found by hand like `take` placeholders,
+    /// which needs a regex for every part between them. Otherwise, the whole format is one regex.
+    pub fn new(format: &FormatString, type_sources: &[Type], staged: bool) -> Result<Self> {
+        let equal_to = format
+            .placeholders
+            .iter()
+            .map(Placeholder::equal_to)
+            .collect::<Result<Vec<_>>>()?;
+        Self::with_equal_to(format, type_sources, &equal_to, staged)
+    }
+
+    /// Same as `new`, but with the placeholder that each `{=N}` placeholder refers to given in
+    /// `equal_to`, like for the `{=field}` placeholders of derive
+    pub fn with_equal_to(
+        format: &FormatString,
+        type_sources: &[Type],
+        equal_to: &[Option<usize>],
+        staged: bool,
+    ) -> Result<Self> {
         let mut ret = Self::empty();
 
         // if there are n types, there are n+1 regex_parts, so add the first n during this loop and
         // add the last one afterwards
-        for ((part, ph), ty) in format
+        // the start of the part after a placeholder that is not a capture group
+        let mut next_prefix = None;
+        for (index, ((part, ph), ty)) in format
             .parts
             .iter()
             .zip(format.placeholders.iter())
             .zip(type_sources)
+            .enumerate()
         {
+            let part = match next_prefix.take() {
+                Some(prefix) => format!("{}{}", prefix, &part[1..]),
+                None => part.clone(),
+            };
+
+            let equal_to = equal_to[index];
+            let interpolation = match equal_to {
+                Some(_) => None,
+                None => ph.interpolation()?,
+            };
+            if let Some(expr) = interpolation {
+                if let Some(config) = ph.config.as_ref() {
+                    let msg = "`{=expr}` placeholders cannot have format options";
+                    return config.src.err(msg); // checked in tests/fail/<channel>/invalid_interpolation.rs
+                }
+                ret.push_literal(&part[..part.len() - 1]);
+                ret.regex_builder.push(RegexPart::Interpolated(expr));
+                ret.literals.push(index);
+                next_prefix = Some("");
+                continue;
+            }
+            if let Some(alternatives) = ph.alternatives() {
+                ret.push_literal(&part[..part.len() - 1]);
+                ret.push_literal(regex_from_alternatives(&alternatives));
+                ret.literals.push(index);
+                next_prefix = Some("");
+                continue;
+            }
+
+            let ph_config = if let Some(equal_to) = equal_to {
+                if let Some(config) = ph.config.as_ref() {
+                    let msg =
+                        "`{=N}` uses the format options of placeholder N and cannot have its own";
+                    return config.src.err(msg); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+                }
+                if ret.literals.contains(&equal_to) {
+                    let msg = "`{=N}` cannot refer to a placeholder without a value";
+                    return ph.src.err(msg); // checked in tests/fail/<channel>/invalid_interpolation.rs
+                }
+                let is_length_take = |t: &Take| {
+                    t.placeholder == equal_to && matches!(t.kind, TakeKind::Length { .. })
+                };
+                if ret.takes.iter().any(is_length_take) {
+                    let msg = "`{=N}` cannot refer to a take placeholder";
+                    return ph.src.err(msg); // checked in tests/fail/<channel>/invalid_equal_placeholder.rs
+                }
+                ret.constraints.push(Constraint {
+                    placeholder: index,
+                    equal_to,
+                    span: ty.full_span(),
+                });
+                format.placeholders[equal_to].config.as_ref()
+            } else {
+                ph.config.as_ref()
+            };
+
+            let take_kind = match ph_config.map(|c| &c.kind) {
+                Some(FormatOptionKind::Take(length_of)) => {
+                    let config = ph_config.unwrap();
+                    if *length_of >= index {
+                        let msg = "take option has to refer to an earlier placeholder";
+                        return config.src.err(msg); // checked in tests/fail/<channel>/invalid_take_option.rs
+                    }
+                    if ret.takes.iter().any(|t| t.placeholder == *length_of) {
+                        let msg = "take option cannot refer to another take placeholder";
+                        return config.src.err(msg); // checked in tests/fail/<channel>/invalid_take_option.rs
+                    }
+                    if ret.literals.contains(length_of) {
+                        let msg = "take option cannot refer to a placeholder without a value";
+                        return config.src.err(msg); // checked in tests/fail/<channel>/invalid_interpolation.rs
+                    }
+                    if !is_string_type(ty) {
+                        let msg = "take option only works on `str`, `String` and `Cow<str>`";
+                        return ty.err(msg); // checked in tests/fail/<channel>/invalid_take_option.rs
+                    }
+
+                    Some(TakeKind::Length {
+                        length_of: *length_of,
+                        length_ty: type_sources[*length_of].inner().clone(),
+                    })
+                }
+                // counting the brackets by hand has no limit on the nesting depth
+                Some(FormatOptionKind::Balanced {
+                    open,
+                    close,
+                    quoted,
+                }) if staged && is_string_type(ty) => Some(TakeKind::Balanced {
+                    open: *open,
+                    close: *close,
+                    quoted: *quoted,
+                }),
+                _ => None,
+            };
+            if let Some(kind) = take_kind {
+                // the placeholder is not part of the regex, so the capture group is removed
+                ret.push_literal(&part[..part.len() - 1]);
+                // the regex after a take starts at the current position of the input
+                next_prefix = Some("^");
+
+                let converter = match ty.kind {
+                    TypeKind::Str(_) => quote! { taken },
+                    TypeKind::CowStr(_) => quote! { ::sscanf::alloc::borrow::Cow::Borrowed(taken) },
+                    TypeKind::Other => quote! { ::sscanf::alloc::string::String::from(taken) },
+                };
+                ret.takes.push(Take {
+                    placeholder: index,
+                    kind,
+                    builder_index: ret.regex_builder.len(),
+                    converter,
+                });
+                continue;
+            }
+
             ret.push_literal(part);
 
             let inner = ty.inner();
             let span = ty.full_span();
 
+            if let Some(types) = ty_check::get_one_of(inner) {
+                if let Some(config) = ph_config {
+                    let msg = "format options cannot be used on placeholders with multiple types";
+                    return config.src.err(msg); // checked in tests/fail/<channel>/invalid_one_of.rs
+                }
+                let (regex, matcher) = regex_from_one_of(inner, span, &types);
+                ret.regex_builder.extend(regex);
+                ret.matchers.push(matcher);
+                continue;
+            }
+
             let mut converter = None;
+            let mut converter_captures = NumCaptures::One;
 
-            let regex = if let Some(config) = ph.config.as_ref() {
+            let regex = if let Some(config) = ph_config {
                 use FormatOptionKind::*;
-                match &config.kind {
+                let builtin;
+                let kind = match &config.kind {
+                    // other types get the option as written, even if it looks like a built-in one
+                    User(_) if is_std_type(ty) => {
+                        builtin = config.builtin_kind()?;
+                        &builtin
+                    }
+                    kind => kind,
+                };
+                match kind {
                     Regex(regex) => RegexPart::Custom(regex.clone()),
-                    Radix { radix, prefix } => {
-                        let (regex, conv) = regex_from_radix(*radix, *prefix, ty)?;
+                    FancyRegex(regex) => {
+                        ret.fancy.push(config.src.error(NEEDS_FANCY));
+                        RegexPart::Custom(regex.clone())
+                    }
+                    RegexConst(path) => RegexPart::Const(path.clone()),
+                    Radix {
+                        radix,
+                        prefix,
+                        twos_complement,
+                    } => {
+                        let (regex, conv) =
+                            regex_from_radix(*radix, *prefix, *twos_complement, ty)?;
                         converter = Some(conv);
                         regex
                     }
                     Hashtag => {
                         return config.src.err("unsupported use of '#'");
                     }
+                    Float {
+                        decimal_comma,
+                        precision,
+                        finite,
+                    } => {
+                        let (regex, conv) =
+                            regex_from_float(*decimal_comma, *precision, *finite, ty)?;
+                        converter = Some(conv);
+                        regex
+                    }
+                    Alternatives(alternatives) if is_string_type(ty) => {
+                        RegexPart::Literal(regex_from_alternatives(alternatives))
+                    }
+                    Alternatives(alternatives) if ty.std_type() == Some("usize") => {
+                        let input = next_capture_str();
+                        converter = Some(Converter::Custom(quote! {{
+                            let text = #input;
+                            [#(#alternatives),*]
+                                .iter()
+                                .position(|alternative| *alternative == text)
+                                .unwrap()
+                        }}));
+                        RegexPart::Literal(regex_from_alternatives(alternatives))
+                    }
+                    Alternatives(_) => {
+                        let msg =
+                            "alternatives only work on `str`, `String`, `Cow<str>` and `usize`";
+                        return ty.err(msg); // checked in tests/fail/<channel>/invalid_alternatives.rs
+                    }
+                    Take(_) => unreachable!("take placeholders are handled above"),
+                    CharSet(class) => regex_from_char_set(class, class, config, ty)?,
+                    User(option) if named_char_set(option).is_some() && is_text_type(ty) => {
+                        let (first, rest) = named_char_set(option).unwrap();
+                        regex_from_char_set(first, rest, config, ty)?
+                    }
+                    Balanced {
+                        open,
+                        close,
+                        quoted,
+                    } if is_string_type(ty) => regex_from_balanced(*open, *close, *quoted),
+                    Balanced { .. } => {
+                        let msg =
+                            "balanced bracket options only work on `str`, `String` and `Cow<str>`";
+                        return ty.err(msg); // checked in tests/fail/<channel>/invalid_balanced_option.rs
+                    }
+                    User(option) if option == "q" && is_string_type(ty) => {
+                        converter = Some(quoted_converter(ty)?);
+                        RegexPart::Custom(QUOTED_REGEX.to_string())
+                    }
+                    Str {
+                        empty,
+                        greedy,
+                        trim,
+                    } if is_string_type(ty) => {
+                        converter = str_trim_converter(*trim, ty);
+                        regex_from_str(*empty, *greedy)
+                    }
+                    Str { .. } if !is_user_option(config.src.text()) => {
+                        let msg = "string options only work on `str`, `String` and `Cow<str>`";
+                        return ty.err(msg); // checked in tests/fail/<channel>/invalid_str_option.rs
+                    }
+                    Str { .. } => {
+                        // no point in checking for FromScanfWithOptions on the types from std
+                        return config.src.err(UNRECOGNIZED_OPTION); // checked in tests/fail/<channel>/raw_string.rs
+                    }
+                    User(_) if is_std_type(ty) => {
+                        return config.src.err(UNRECOGNIZED_OPTION); // checked in tests/fail/<channel>/raw_string.rs
+                    }
+                    User(option) => {
+                        let call = span.apply(
+                            quote! { ::sscanf::FromScanfWithOptions },
+                            quote! { ::from_matches_with(#option, &mut *src) },
+                        );
+                        converter = Some(Converter::Custom(quote! {{
+                            let value: #inner = #call?;
+                            value
+                        }}));
+                        converter_captures = NumCaptures::WithOptions(inner.clone(), span);
+                        RegexPart::WithOptions(inner.clone(), span, option.to_string())
+                    }
                 }
             } else {
                 match ty.kind {
@@ -208,14 +628,26 @@ impl RegexParts {
             };
             ret.regex_builder.push(regex);
 
-            let (num_captures, converter) = match ty.kind {
-                TypeKind::Str(_) => (NumCaptures::One, Converter::Str),
-                TypeKind::CowStr(_) => (NumCaptures::One, Converter::CowStr),
-                TypeKind::Other => (
+            let (mut num_captures, mut converter) = match (converter, &ty.kind) {
+                (Some(converter), _) => (converter_captures, converter),
+                (None, TypeKind::Str(_)) => (NumCaptures::One, Converter::Str),
+                (None, TypeKind::CowStr(_)) => (NumCaptures::One, Converter::CowStr),
+                (None, TypeKind::Other) if ph_config.is_none() => from_type_with_regex(inner, span),
+                (None, TypeKind::Other) => (
                     NumCaptures::FromType(inner.clone(), span),
-                    converter.unwrap_or_else(|| Converter::FromType(inner.clone(), span)),
+                    Converter::FromType(inner.clone(), span),
                 ),
             };
+            if let Some(FormatOptionKind::RegexConst(path)) = ph_config.map(|c| &c.kind) {
+                // capture groups in the constant are skipped after the conversion
+                num_captures =
+                    NumCaptures::Sum(vec![num_captures, NumCaptures::RegexConst(path.clone())]);
+                converter = Converter::Custom(quote! {{
+                    let value = #converter;
+                    (&mut *src).take(::sscanf::capture_groups(#path)).for_each(drop);
+                    value
+                }});
+            }
 
             ret.matchers.push(Matcher {
                 ty: inner.clone(),
@@ -227,7 +659,10 @@ impl RegexParts {
         // add the last regex_part
         {
             let suffix = format.parts.last().unwrap();
-            ret.push_literal(suffix);
+            match next_prefix {
+                Some(prefix) => ret.push_literal(format!("{}{}", prefix, &suffix[1..])),
+                None => ret.push_literal(suffix),
+            }
         }
 
         Ok(ret)
@@ -248,22 +683,95 @@ impl RegexParts {
         let num_captures = self.num_captures_list();
         quote! { #(#num_captures)+* }
     }
+
+    /// Code that runs `mismatch` if a `{=N}` placeholder is not equal to placeholder `N`.
+    /// `value` is the name of the variable that contains the value of a placeholder.
+    pub fn constraint_checks(
+        &self,
+        value: impl Fn(usize) -> syn::Ident,
+        mismatch: TokenStream,
+    ) -> TokenStream {
+        let checks = self.constraints.iter().map(|c| {
+            let (a, b) = (value(c.placeholder), value(c.equal_to));
+            let ne = c
+                .span
+                .apply(quote! { ::core::cmp::PartialEq }, quote! { ::ne(&#a, &#b) });
+            quote! {
+                if #ne {
+                    #mismatch
+                }
+            }
+        });
+        quote! { #(#checks)* }
+    }
+
+    fn placeholder_count(&self) -> usize {
+        self.matchers.len() + self.takes.len() + self.literals.len()
+    }
+
+    /// Indices of the placeholders that have a matcher, in the same order as `matchers`
+    pub fn matcher_indices(&self) -> Vec<usize> {
+        (0..self.placeholder_count())
+            .filter(|i| !self.takes.iter().any(|t| t.placeholder == *i))
+            .filter(|i| !self.literals.contains(i))
+            .collect()
+    }
+
+    /// Indices of the placeholders that are part of the output, which excludes `{=N}`, `{=expr}`
+    /// and `{(a|b)}` placeholders
+    pub fn output_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.placeholder_count())
+            .filter(move |i| !self.constraints.iter().any(|c| c.placeholder == *i))
+            .filter(move |i| !self.literals.contains(i))
+    }
+
+    /// Splits the regex and the matchers at the `take` placeholders
+    pub fn stages(&self) -> Vec<Stage<'_>> {
+        let matcher_indices = self.matcher_indices();
+        let mut stages = vec![];
+        let mut builder_start = 0;
+        let mut matcher_start = 0;
+        for take in self.takes.iter().map(Some).chain(std::iter::once(None)) {
+            let (builder_end, matcher_end) = match take {
+                Some(take) => (
+                    take.builder_index,
+                    matcher_indices
+                        .iter()
+                        .take_while(|i| **i < take.placeholder)
+                        .count(),
+                ),
+                None => (self.regex_builder.len(), self.matchers.len()),
+            };
+            let matchers = &self.matchers[matcher_start..matcher_end];
+            let num_captures =
+                std::iter::once(NumCaptures::One) // for the whole match
+                    .chain(matchers.iter().map(|m| m.num_captures.clone()));
+            stages.push(Stage {
+                regex_builder: &self.regex_builder[builder_start..builder_end],
+                num_captures: quote! { #(#num_captures)+* },
+                matchers,
+                take,
+            });
+            builder_start = builder_end;
+            matcher_start = matcher_end;
+        }
+        stages
+    }
 }
 
 fn regex_from_radix(
     radix: u8,
     prefix_policy: PrefixPolicy,
+    twos_complement: bool,
     ty: &Type,
 ) -> Result<(RegexPart, Converter)> {
-    let ty_string = ty.to_token_stream().to_string();
-
-    let num_digits_binary = binary_length(&ty_string).ok_or_else(|| {
-        let msg = "radix options only work on primitive numbers from std with no path or alias";
-        ty.error(msg) // checked in tests/fail/<channel>/invalid_radix_option.rs
-    })?;
-
-    let signed = ty_string.starts_with('i');
-    let sign = if signed { "[-+]?" } else { "\\+?" };
+    if let Some("f32") | Some("f64") = ty.std_type() {
+        if twos_complement {
+            let msg = "two's complement modifier '~' only works on integers";
+            return ty.err(msg); // checked in tests/fail/<channel>/invalid_radix_option.rs
+        }
+        return regex_from_hex_float(radix, prefix_policy, ty);
+    }
 
     let prefix_string = match prefix_policy {
         PrefixPolicy::Optional(prefix) => format!("(?:{})?", prefix),
@@ -282,99 +790,362 @@ fn regex_from_radix(
         }
     };
 
-    let num_digits = if radix == 2 {
-        num_digits_binary
+    let span = ty.full_span();
+    let ty = ty.inner();
+
+    let regex = RegexPart::Radix {
+        ty: ty.clone(),
+        span,
+        prefix: prefix_string,
+        digits: possible_chars,
+        radix: radix as u32,
+        twos_complement,
+    };
+
+    let get_input = next_capture_str();
+    let radix = radix as u32;
+
+    let parse = |input: TokenStream| {
+        let method = if twos_complement {
+            quote! { from_twos_complement_str_radix }
+        } else {
+            quote! { from_str_radix }
+        };
+        // the arguments and the `?` are part of the span, so that all errors point at the type
+        let call = radix_trait_member(ty, span, quote! { #method(#input, #radix)? });
+        quote! {{
+            let value: #ty = #call;
+            value
+        }}
+    };
+
+    let (prefix, no_prefix_handler) = match prefix_policy {
+        PrefixPolicy::Never => {
+            let parse_input = parse(quote! { input });
+            let converter = quote! {{
+                let input = #get_input;
+                #parse_input
+            }};
+            return Ok((regex, Converter::Custom(converter)));
+        }
+        PrefixPolicy::Optional(prefix) => (prefix, quote! { /* do nothing */ }),
+        PrefixPolicy::Forced(prefix) => (
+            prefix,
+            quote! {
+                ::core::option::Option::None.ok_or(::sscanf::errors::MissingPrefixError::#prefix)?;
+                #[allow(unreachable_code)]
+            },
+        ),
+    };
+    let prefix_lowercase = prefix.to_string();
+    let prefix_uppercase = prefix_lowercase.to_uppercase();
+    let prefix_matcher = quote! {
+        no_sign.strip_prefix(#prefix_lowercase).or_else(|| no_sign.strip_prefix(#prefix_uppercase))
+    };
+
+    let converter = if twos_complement {
+        // no sign in front of the bit pattern
+        let parse_no_prefix = parse(quote! { no_prefix });
+        quote! {{
+            let no_sign = #get_input;
+            let no_prefix = if let ::core::option::Option::Some(no_prefix) = #prefix_matcher {
+                no_prefix
+            } else {
+                #no_prefix_handler
+                no_sign
+            };
+            #parse_no_prefix
+        }}
     } else {
-        // digit conversion:   num_digits_in_base_a = num_digits_in_base_b * log(b) / log(a)
-        // where log can be any type of logarithm. Since binary is base 2 and log_2(2) = 1,
-        // we can use log_2 to simplify the math
-        f32::ceil(num_digits_binary as f32 / f32::log2(radix as f32)) as u32
+        let parse_negative = parse(quote! { &input });
+        let parse_positive = parse(quote! { no_sign_prefix });
+        let parse_input = parse(quote! { input });
+        quote! {{
+            let input = #get_input;
+            let (negative, no_sign) = match input.strip_prefix('-') {
+                ::core::option::Option::Some(no_sign) => (true, no_sign),
+                ::core::option::Option::None => (false, input.strip_prefix('+').unwrap_or(input)),
+            };
+            if let ::core::option::Option::Some(no_sign_prefix) = #prefix_matcher {
+                if negative {
+                    // re-package `no_sign_prefix` into a string that includes the sign, because otherwise
+                    // it might cause faulty overflow errors on numbers like -128i8
+                    let input = ::sscanf::alloc::format!("-{}", no_sign_prefix);
+                    #parse_negative
+                } else {
+                    #parse_positive
+                }
+            } else {
+                #no_prefix_handler
+                #parse_input // note the use of `input` here to include the sign
+            }
+        }}
     };
 
-    let regex = format!(
-        "(?i:{sign}{prefix}[{digits}]{{1,{n}}})",
-        sign = sign,
-        prefix = prefix_string,
-        digits = possible_chars,
-        n = num_digits
-    );
+    Ok((regex, Converter::Custom(converter)))
+}
 
-    // we know ty is a primitive type without path, which are always just one token
-    // => no Span voodoo necessary
-    let ty = ty.inner();
-    let span = ty.span();
+/// Named ASCII character classes, similar to the ones from C's `<ctype.h>`.
+///
+/// Returns the class of the first character and the class of all following characters.
+fn named_char_set(name: &str) -> Option<(&'static str, &'static str)> {
+    let class = match name {
+        "alpha" => "[a-zA-Z]",
+        "alnum" => "[a-zA-Z0-9]",
+        "digit" => "[0-9]",
+        "xdigit" => "[0-9a-fA-F]",
+        "upper" => "[A-Z]",
+        "lower" => "[a-z]",
+        "space" => r"[ \t\n\r\x0B\x0C]",
+        "ident" => return Some(("[a-zA-Z_]", "[a-zA-Z0-9_]")),
+        _ => return None,
+    };
+    Some((class, class))
+}
+
+/// `str`, `String`, `Cow<str>` and `char`, which can be used with character set options
+fn is_text_type(ty: &Type) -> bool {
+    is_string_type(ty) || ty.std_type() == Some("char")
+}
 
-    let get_input = quote! { src.next()
-        .expect(::sscanf::errors::EXPECT_NEXT_HINT)
-        .expect(::sscanf::errors::EXPECT_CAPTURE_HINT)
-        .as_str()
+fn regex_from_char_set(
+    first: &str,
+    rest: &str,
+    config: &FormatOption,
+    ty: &Type,
+) -> Result<RegexPart> {
+    if !is_text_type(ty) {
+        let msg = "character set options only work on `str`, `String`, `Cow<str>` and `char`";
+        return ty.err(msg); // checked in tests/fail/<channel>/invalid_char_set.rs
+    }
+
+    let regex = if ty.std_type() == Some("char") {
+        if first != rest {
+            let msg = "this character set only works on `str`, `String` and `Cow<str>`";
+            return config.src.err(msg); // checked in tests/fail/<channel>/invalid_char_set.rs
+        }
+        first.to_string()
+    } else if first == rest {
+        format!("{}+", first)
+    } else {
+        format!("{}{}*", first, rest)
     };
+    Ok(RegexPart::Custom(regex))
+}
 
-    fn create_converter(
-        ty: &syn::Type,
-        radix: u32,
-        signed: bool,
-        prefix_policy: PrefixPolicy,
-        get_input: TokenStream,
-    ) -> TokenStream {
-        let (prefix, no_prefix_handler) = match prefix_policy {
-            PrefixPolicy::Never => {
-                return quote! {{
-                    let input = #get_input;
-                    #ty::from_str_radix(input, #radix)?
-                }};
+const NEEDS_FANCY: &str =
+    "this custom regex needs `fancy-regex`, which cannot be used with regex options or in derive";
+
+/// Whether a regex part can only be built in Unicode mode, because it matches arbitrary characters.
+/// Parts that are only known at runtime return an `Error::InvalidRegex` instead.
+pub fn needs_unicode(part: &RegexPart) -> bool {
+    match part {
+        RegexPart::Custom(regex) => regex_syntax::ParserBuilder::new()
+            .unicode(false)
+            .build()
+            .parse(regex)
+            .is_err(),
+        RegexPart::FromType(ty, _) => {
+            ty_check::get_str(ty).is_some()
+                || matches!(ty_check::get_std_type(ty), Some("String") | Some("char"))
+        }
+        _ => false,
+    }
+}
+
+/// `str`, `String` and `Cow<str>`, which can be used with string options
+fn is_string_type(ty: &Type) -> bool {
+    !matches!(ty.kind, TypeKind::Other) || ty.std_type() == Some("String")
+}
+
+fn regex_from_str(empty: bool, greedy: bool) -> RegexPart {
+    let repetition = if empty { "*" } else { "+" };
+    let laziness = if greedy { "" } else { "?" };
+    RegexPart::Custom(format!(".{}{}", repetition, laziness))
+}
+
+/// Non-capturing regex that matches one of the alternatives as literal text
+fn regex_from_alternatives(alternatives: &[String]) -> String {
+    let alternatives = alternatives
+        .iter()
+        .map(|alternative| regex_syntax::escape(alternative))
+        .collect::<Vec<_>>();
+    format!("(?:{})", alternatives.join("|"))
+}
+
+/// Regex and matcher for `OneOfN<A, B, ...>`: an alternation of the regexes of all types, where
+/// every type has its own capture group, so that the converter can check which one matched.
+fn regex_from_one_of(
+    ty: &syn::Type,
+    span: FullSpan,
+    types: &[syn::Type],
+) -> (Vec<RegexPart>, Matcher) {
+    let mut regex = vec![];
+    let mut branches = vec![];
+    for (i, branch) in types.iter().enumerate() {
+        regex.push(RegexPart::Literal(
+            if i == 0 { "(" } else { ")|(" }.to_string(),
+        ));
+        let (part, num_captures, converter) = match TypeKind::from_ty(branch) {
+            TypeKind::Str(_) => (syn::parse_quote!(str), NumCaptures::One, Converter::Str),
+            TypeKind::CowStr(_) => (syn::parse_quote!(str), NumCaptures::One, Converter::CowStr),
+            TypeKind::Other => {
+                let (num_captures, converter) = from_type_with_regex(branch, span);
+                (branch.clone(), num_captures, converter)
             }
-            PrefixPolicy::Optional(prefix) => (prefix, quote! { /* do nothing */ }),
-            PrefixPolicy::Forced(prefix) => (
-                prefix,
-                quote! {
-                    ::std::option::Option::None.ok_or(::sscanf::errors::MissingPrefixError::#prefix)?;
-                    #[allow(unreachable_code)]
-                },
-            ),
-        };
-        let prefix_lowercase = prefix.to_string();
-        let prefix_uppercase = prefix_lowercase.to_uppercase();
-        let prefix_matcher = quote! {
-            no_sign.strip_prefix(#prefix_lowercase).or_else(|| no_sign.strip_prefix(#prefix_uppercase))
         };
+        regex.push(RegexPart::FromType(part, span));
+        branches.push((num_captures, converter));
+    }
+    regex.push(RegexPart::Literal(")".to_string()));
 
-        if signed {
-            quote! {{
-                let input = #get_input;
-                let (negative, no_sign) = match input.strip_prefix('-') {
-                    ::std::option::Option::Some(no_sign) => (true, no_sign),
-                    ::std::option::Option::None => (false, input.strip_prefix('+').unwrap_or(input)),
-                };
-                if let ::std::option::Option::Some(no_sign_prefix) = #prefix_matcher {
-                    if negative {
-                        // re-package `no_sign_prefix` into a string that includes the sign, because otherwise
-                        // it might cause faulty overflow errors on numbers like -128i8
-                        let input = ::std::format!("-{}", no_sign_prefix);
-                        #ty::from_str_radix(&input, #radix)?
-                    } else {
-                        #ty::from_str_radix(no_sign_prefix, #radix)?
-                    }
-                } else {
-                    #no_prefix_handler
-                    #ty::from_str_radix(input, #radix)? // note the use of `input` here to include the sign
-                }
-            }}
-        } else {
-            quote! {{
-                let input = #get_input;
-                let no_sign = input.strip_prefix('+').unwrap_or(input);
-                if let ::std::option::Option::Some(no_sign_prefix) = #prefix_matcher {
-                    #ty::from_str_radix(no_sign_prefix, #radix)?
-                } else {
-                    #no_prefix_handler
-                    #ty::from_str_radix(no_sign, #radix)?
-                }
-            }}
+    let name = match ty {
+        syn::Type::Path(path) => path.path.segments.last().unwrap().ident.clone(),
+        _ => unreachable!("OneOf types are always paths"),
+    };
+    let num_captures = branches.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>();
+    let mut checks = vec![];
+    for (i, (_, converter)) in branches.iter().enumerate() {
+        let variant = quote::format_ident!("{}", (b'A' + i as u8) as char);
+        let before = NumCaptures::Sum(num_captures[..i].to_vec());
+        let after = NumCaptures::Sum(num_captures[i + 1..].to_vec());
+        checks.push(quote! {
+            if src.peek_nth(#before).flatten().is_some() {
+                (&mut *src).take(#before).for_each(drop);
+                let value = #converter;
+                (&mut *src).take(#after).for_each(drop);
+                ::sscanf::#name::#variant(value)
+            }
+        });
+    }
+    let converter = quote! {{
+        src.skip_captures(1, stringify!(#ty)); // the whole placeholder
+        #(#checks)else* else {
+            // none of the types participated in the match
+            return ::core::result::Result::Err(src.invalid(stringify!(#ty), 1, 0).into());
         }
+    }};
+
+    let mut captures = vec![NumCaptures::One]; // for the whole placeholder
+    captures.extend(num_captures);
+    let matcher = Matcher {
+        ty: ty.clone(),
+        num_captures: NumCaptures::Sum(captures),
+        converter: Converter::Custom(converter),
+    };
+    (regex, matcher)
+}
+
+/// Nesting depth up to which balanced brackets are matched by the regex
+const MAX_BALANCED_DEPTH: usize = 16;
+
+/// Regex for text where every `open` has a matching `close`. With `quoted`, brackets inside of
+/// `"..."` or `'...'` are ignored.
+///
+/// A regex cannot count, so only `MAX_BALANCED_DEPTH` levels are matched. This is only used
+/// where the format has to be a single regex, since `sscanf!` counts the brackets by hand.
+fn regex_from_balanced(open: char, close: char, quoted: bool) -> RegexPart {
+    let (open, close) = (
+        regex_syntax::escape(&open.to_string()),
+        regex_syntax::escape(&close.to_string()),
+    );
+    let other = if quoted {
+        format!(
+            r#"(?:[^{}{}"']|"(?:[^"\\]|\\(?s:.))*"|'(?:[^'\\]|\\(?s:.))*')*"#,
+            open, close
+        )
+    } else {
+        format!("[^{}{}]*", open, close)
+    };
+
+    // level n: `other (open <level n-1> close other)*`
+    let mut regex = other.clone();
+    for _ in 0..MAX_BALANCED_DEPTH {
+        regex = format!("{}(?:{}{}{}{})*", other, open, regex, close, other);
     }
+    RegexPart::Custom(regex)
+}
+
+/// Converter that trims the matched text, if requested. Otherwise the default converter is used.
+fn str_trim_converter(trim: bool, ty: &Type) -> Option<Converter> {
+    if !trim {
+        return None;
+    }
+    // trimming a `&str` returns a sub-slice, so the borrow from the input is kept
+    let trimmed = next_capture_str();
+    let trimmed = quote! { #trimmed.trim() };
+    let converter = match ty.kind {
+        TypeKind::Str(_) => trimmed,
+        TypeKind::CowStr(_) => quote! { ::sscanf::alloc::borrow::Cow::Borrowed(#trimmed) },
+        TypeKind::Other => quote! { ::sscanf::alloc::string::String::from(#trimmed) },
+    };
+    Some(Converter::Custom(converter))
+}
+
+/// A double- or single-quoted string, where the quote character can be escaped with a backslash
+const QUOTED_REGEX: &str = r#"(?:"(?:[^"\\]|\\(?s:.))*"|'(?:[^'\\]|\\(?s:.))*')"#;
+
+/// Converter for the `q` option, which removes the quotes and resolves escape sequences
+fn quoted_converter(ty: &Type) -> Result<Converter> {
+    let input = next_capture_str();
+    let unescaped = quote! { ::sscanf::unescape_quoted(#input)? };
+    let converter = match ty.kind {
+        TypeKind::Str(_) => {
+            let msg = "`q` has to unescape the text and cannot return a `&str`. Use `Cow<str>` or `String` instead";
+            return ty.err(msg); // checked in tests/fail/<channel>/invalid_quoted_option.rs
+        }
+        TypeKind::CowStr(_) => unescaped,
+        TypeKind::Other => quote! { #unescaped.into_owned() },
+    };
+    Ok(Converter::Custom(converter))
+}
+
+/// Types from std that are known to not implement `FromScanfWithOptions`
+fn is_std_type(ty: &Type) -> bool {
+    // str and Cow<str> are not `TypeKind::Other`
+    !matches!(ty.kind, TypeKind::Other) || ty.std_type().is_some()
+}
 
-    let converter = create_converter(ty, radix as u32, signed, prefix_policy, get_input);
+/// Code for `<#ty as ::sscanf::ScanfRadix>::#member`
+fn radix_trait_member(ty: &syn::Type, span: FullSpan, member: TokenStream) -> TokenStream {
+    // See the comment in `NumCaptures::FromType` for an explanation of the span
+    let mut tokens = span.apply_start(quote! { < });
+    ty.to_tokens(&mut tokens);
+    tokens.extend(span.apply(quote! { as }, quote! { ::sscanf::ScanfRadix >::#member }));
+    tokens
+}
+
+fn regex_from_hex_float(
+    radix: u8,
+    prefix_policy: PrefixPolicy,
+    ty: &Type,
+) -> Result<(RegexPart, Converter)> {
+    if radix != 16 {
+        let msg = "floats only support hexadecimal radix options: `x`, `#x` or `r16`";
+        return ty.err(msg); // checked in tests/fail/<channel>/invalid_radix_option.rs
+    }
+
+    let prefix = match prefix_policy {
+        PrefixPolicy::Optional(prefix) => format!("(?:{})?", prefix),
+        PrefixPolicy::Forced(prefix) => prefix.to_string(),
+        PrefixPolicy::Never => String::new(),
+    };
+
+    // grammar of C's `%a`: an optional fractional part and a mandatory binary exponent
+    let regex = format!(
+        r"(?i:[+-]?{}(?:[0-9a-f]+(?:\.[0-9a-f]*)?|\.[0-9a-f]+)p[+-]?\d+)",
+        prefix
+    );
+
+    // f32 and f64 always implement the traits => no Span voodoo necessary
+    let ty = ty.inner();
+    let span = ty.span();
+
+    let get_input = next_capture_str();
+    let converter = quote! {{
+        let input = #get_input;
+        <#ty as ::sscanf::HexFloat>::from_hex_str(input)?
+    }};
 
     Ok((
         RegexPart::Custom(regex),
@@ -382,14 +1153,54 @@ fn regex_from_radix(
     ))
 }
 
-fn binary_length(ty: &str) -> Option<u32> {
-    match ty {
-        "u8" | "i8" => Some(u8::BITS),
-        "u16" | "i16" => Some(u16::BITS),
-        "u32" | "i32" => Some(u32::BITS),
-        "u64" | "i64" => Some(u64::BITS),
-        "u128" | "i128" => Some(u128::BITS),
-        "usize" | "isize" => Some(usize::BITS),
-        _ => None,
+fn regex_from_float(
+    decimal_comma: bool,
+    precision: Option<u8>,
+    finite: bool,
+    ty: &Type,
+) -> Result<(RegexPart, Converter)> {
+    if !matches!(ty.std_type(), Some("f32") | Some("f64")) {
+        let msg = "float options only work on `f32` and `f64`";
+        return ty.err(msg); // checked in tests/fail/<channel>/invalid_float_option.rs
     }
+
+    // same grammar as the default float regex in sscanf/src/regex_representation.rs, but with
+    // a configurable decimal separator and fractional part
+    let separator = if decimal_comma { "," } else { r"\." };
+    let number = match precision {
+        Some(0) => r"\d+".to_string(),
+        Some(n) => format!(r"\d+{}\d{{{}}}", separator, n),
+        None => format!(
+            r"(?:\d+|\d+{sep}\d*|\d*{sep}\d+)(?:e[+-]?\d+)?",
+            sep = separator
+        ),
+    };
+    let regex = if finite {
+        format!("(?i:[+-]?{})", number)
+    } else {
+        format!("(?i:[+-]?(?:inf|infinity|nan|{}))", number)
+    };
+
+    // f32 and f64 always implement the traits => no Span voodoo necessary
+    let ty = ty.inner();
+    let span = ty.span();
+
+    let get_input = next_capture_str();
+    let converter = if decimal_comma {
+        // normalize the text so that `FromStr` can handle it
+        quote! {{
+            let input = #get_input.replace(',', ".");
+            <#ty as ::core::str::FromStr>::from_str(&input)?
+        }}
+    } else {
+        quote! {{
+            let input = #get_input;
+            <#ty as ::core::str::FromStr>::from_str(input)?
+        }}
+    };
+
+    Ok((
+        RegexPart::Custom(regex),
+        Converter::Custom(converter.with_span(span)),
+    ))
 }
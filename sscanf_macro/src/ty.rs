@@ -43,6 +43,16 @@ impl<'a> Type<'a> {
     pub fn lifetime(&self) -> Option<&syn::Lifetime> {
         self.kind.lifetime()
     }
+    /// The name of the primitive type or `String` that this type refers to, see
+    /// [`ty_check::get_std_type`]
+    pub fn std_type(&self) -> Option<&'static str> {
+        ty_check::get_std_type(&self.ty)
+    }
+    /// Whether this is one of the primitive integer types, which implement `ScanfRadix`
+    pub fn is_primitive_int(&self) -> bool {
+        self.std_type()
+            .map_or(false, |name| ty_check::PRIMITIVE_INTS.contains(&name))
+    }
     pub fn err<T, U: std::fmt::Display>(&self, message: U) -> Result<T> {
         Err(self.error(message))
     }
@@ -53,6 +63,9 @@ impl<'a> Type<'a> {
         }
     }
     pub fn from_str(src: StrLitSlice<'a>) -> syn::Result<Self> {
+        if src.text().contains('|') {
+            return Self::one_of_from_str(src);
+        }
         let span = src.span();
 
         let tokens = src.text().parse::<TokenStream>()?.with_span(span);
@@ -60,6 +73,37 @@ impl<'a> Type<'a> {
         ty.source = TypeSource::Format(src);
         Ok(ty)
     }
+
+    /// Parses `A|B|...` into `::sscanf::OneOfN<A, B, ...>`
+    fn one_of_from_str(src: StrLitSlice<'a>) -> syn::Result<Self> {
+        let span = src.span();
+        let mut types = vec![];
+        let mut start = 0;
+        for part in src.text().split('|') {
+            if part.trim().is_empty() {
+                let msg = "expected a type before and after every `|`";
+                return Err(syn::Error::new(span, msg)); // checked in tests/fail/<channel>/invalid_one_of.rs
+            }
+            let ty = Self::from_str(src.slice(start..start + part.len()))?;
+            start += part.len() + 1;
+            types.push(match ty.kind {
+                TypeKind::Str(_) => {
+                    let inner = ty.inner();
+                    quote! { &#inner }.with_span(span)
+                }
+                _ => ty.into_inner().into_token_stream(),
+            });
+        }
+        if types.len() > ty_check::MAX_ONE_OF {
+            let msg = format!("at most {} types are allowed", ty_check::MAX_ONE_OF);
+            return Err(syn::Error::new(span, msg)); // checked in tests/fail/<channel>/invalid_one_of.rs
+        }
+        let name = quote::format_ident!("OneOf{}", types.len());
+        let tokens = quote! { ::sscanf::#name<#(#types),*> }.with_span(span);
+        let mut ty = syn::parse2::<Type>(tokens)?;
+        ty.source = TypeSource::Format(src);
+        Ok(ty)
+    }
 }
 
 impl TypeKind {
@@ -126,6 +170,50 @@ pub mod ty_check {
         let ty = ty.to_token_stream().to_string();
         possible.iter().any(|p| p.to_string() == ty)
     }
+    /// Primitive integer types
+    pub const PRIMITIVE_INTS: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    /// Other primitive types that sscanf implements the traits for
+    const OTHER_PRIMITIVES: &[&str] = &["f32", "f64", "bool", "char"];
+
+    /// Returns the name of a primitive type like `u8` or `std::primitive::u8`, or `"String"` for
+    /// `String` or `std::string::String`.
+    ///
+    /// Type aliases can't be resolved by a macro, so they are not recognized.
+    pub fn get_std_type(ty: &syn::Type) -> Option<&'static str> {
+        let ty = match ty {
+            syn::Type::Path(ty) if ty.qself.is_none() => ty,
+            _ => return None,
+        };
+        let mut segments = ty.path.segments.iter().rev();
+        let seg = segments.next()?;
+        if !matches!(seg.arguments, syn::PathArguments::None) {
+            return None;
+        }
+        let (name, module, roots): (_, _, &[&str]) = if seg.ident == "String" {
+            ("String", "string", &["std", "alloc"])
+        } else {
+            let name = PRIMITIVE_INTS
+                .iter()
+                .chain(OTHER_PRIMITIVES)
+                .find(|name| seg.ident == name)?;
+            (*name, "primitive", &["std", "core"])
+        };
+        match (segments.next(), segments.next(), segments.next()) {
+            (None, None, None) if ty.path.leading_colon.is_none() => Some(name),
+            (Some(m), None, None) if ty.path.leading_colon.is_none() && is_segment(m, module) => {
+                Some(name)
+            }
+            (Some(m), Some(root), None)
+                if is_segment(m, module) && roots.iter().any(|r| is_segment(root, r)) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_str(ty: &syn::Type) -> Option<Option<syn::Lifetime>> {
         match ty {
             syn::Type::Path(ref ty) => {
@@ -206,6 +294,45 @@ pub mod ty_check {
                 .or_else(|| get_cow_segment(iter))
         }
     }
+    /// Maximum number of types in a `OneOfN` enum
+    pub const MAX_ONE_OF: usize = 6;
+
+    /// Returns the types of `OneOfN<A, B, ...>`, `sscanf::OneOfN<...>` or `::sscanf::OneOfN<...>`
+    pub fn get_one_of(ty: &syn::Type) -> Option<Vec<syn::Type>> {
+        let ty = match ty {
+            syn::Type::Path(ty) if ty.qself.is_none() => ty,
+            _ => return None,
+        };
+        let mut segments = ty.path.segments.iter().rev();
+        let seg = segments.next()?;
+        match (segments.next(), segments.next()) {
+            (None, None) if ty.path.leading_colon.is_none() => {}
+            (Some(root), None) if is_segment(root, "sscanf") => {}
+            _ => return None,
+        }
+        let n = seg
+            .ident
+            .to_string()
+            .strip_prefix("OneOf")?
+            .parse::<usize>()
+            .ok()?;
+        let args = match &seg.arguments {
+            syn::PathArguments::AngleBracketed(args) => &args.args,
+            _ => return None,
+        };
+        let types = args
+            .iter()
+            .map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if !(2..=MAX_ONE_OF).contains(&n) || types.len() != n {
+            return None;
+        }
+        Some(types)
+    }
+
     pub fn get_cow_str(ty: &syn::Type) -> Option<Option<syn::Lifetime>> {
         match ty {
             syn::Type::Path(ref ty) => get_cow_str_path(ty),
@@ -186,3 +186,34 @@ fn autogen_cases() {
 
     assert!(errors.is_empty(), "{}", errors);
 }
+
+#[test]
+fn regex_options() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(autogen, regex_options(size_limit = 1 << 24))]
+    enum Level {
+        Low,
+        High,
+    }
+    assert_eq!(
+        <Level as RegexRepresentation>::REGEX_OPTIONS,
+        RegexOptions::new().size_limit(1 << 24)
+    );
+    assert_eq!(Level::from_str("High").unwrap(), Level::High);
+}
+
+#[test]
+fn equal_placeholders() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    enum Pair {
+        #[sscanf("{0}*2")]
+        Double(u32),
+        #[sscanf("{a}+{=a}")]
+        Twice { a: u32 },
+    }
+
+    assert_eq!(Pair::from_str("3*2").unwrap(), Pair::Double(3));
+    assert_eq!(Pair::from_str("3+3").unwrap(), Pair::Twice { a: 3 });
+    let err = Pair::from_str("3+4").unwrap_err();
+    assert!(matches!(err, Error::MatchFailed), "{:?}", err);
+}
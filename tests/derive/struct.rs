@@ -117,3 +117,137 @@ fn transparent() {
     let ret = sscanf!("5", "{TestStructNamed}").unwrap();
     assert_eq!(ret, TestStructNamed { a: 5 });
 }
+
+#[test]
+fn alternatives() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{count} file{(|s)} {state:(added|removed)}")]
+    struct Change {
+        count: usize,
+        state: usize,
+    }
+
+    let ret = sscanf!("1 file removed", "{Change}").unwrap();
+    assert_eq!(ret, Change { count: 1, state: 1 });
+    let ret = sscanf!("3 files added", "{Change}").unwrap();
+    assert_eq!(ret, Change { count: 3, state: 0 });
+    sscanf!("3 filez added", "{Change}").unwrap_err();
+}
+
+#[test]
+fn equal_placeholders() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "<{tag}>{text}</{=tag}>")]
+    struct Element<'a> {
+        tag: &'a str,
+        text: &'a str,
+    }
+
+    let ret = sscanf!("<b>bold</b>", "{Element}").unwrap();
+    assert_eq!(
+        ret,
+        Element {
+            tag: "b",
+            text: "bold"
+        }
+    );
+    let err = sscanf!("<b>bold</i>", "{Element}").unwrap_err();
+    assert!(matches!(err, Error::MatchFailed), "{:?}", err);
+    let err = Element::from_str("<b>bold</i>").unwrap_err();
+    assert!(matches!(err, Error::MatchFailed), "{:?}", err);
+
+    // `{=N}` refers to a placeholder and uses its format options
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{:x} == {=0}")]
+    struct Hex(u8);
+
+    assert_eq!(Hex::from_str("1F == 0x1f").unwrap(), Hex(31));
+    let err = Hex::from_str("1F == 0x1e").unwrap_err();
+    assert!(matches!(err, Error::MatchFailed), "{:?}", err);
+}
+
+#[test]
+fn one_of() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "timeout={timeout}")]
+    struct Config<'a> {
+        timeout: OneOf2<u64, &'a str>,
+    }
+
+    let ret = sscanf!("timeout=30", "{Config}").unwrap();
+    assert_eq!(ret.timeout, OneOf2::A(30));
+    let ret = sscanf!("timeout=never", "{Config}").unwrap();
+    assert_eq!(ret.timeout, OneOf2::B("never"));
+}
+
+#[test]
+fn balanced() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{name}({args:()})")]
+    struct Call<'a> {
+        name: &'a str,
+        args: &'a str,
+    }
+
+    let call = Call::from_str("f(g(x), h())").unwrap();
+    assert_eq!(
+        call,
+        Call {
+            name: "f",
+            args: "g(x), h()"
+        }
+    );
+
+    // the regex of derive can only match up to 16 levels of nesting
+    let inner = format!("{}x{}", "(".repeat(16), ")".repeat(16));
+    let input = format!("f({})", inner);
+    assert_eq!(Call::from_str(&input).unwrap().args, inner);
+    let input = format!("f(({}))", inner);
+    assert!(matches!(Call::from_str(&input), Err(Error::MatchFailed)));
+}
+
+#[test]
+fn regex_options() {
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{x},{y}", regex_options(unicode = false, nest_limit = 100))]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+    assert_eq!(
+        <Point as RegexRepresentation>::REGEX_OPTIONS,
+        RegexOptions::new().unicode(false).nest_limit(100)
+    );
+    assert_eq!(Point::from_str("1,2").unwrap(), Point { x: 1, y: 2 });
+    assert!(matches!(Point::from_str("1,٣"), Err(Error::MatchFailed)));
+
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{x} {name}", regex_options(unicode = false))]
+    struct Named {
+        x: u8,
+        name: String,
+    }
+    let parsed = Named::from_str("1 Zoë").unwrap();
+    assert_eq!(
+        parsed,
+        Named {
+            x: 1,
+            name: String::from("Zoë")
+        }
+    );
+
+    // the regex of a sscanf call only uses its own options
+    let err = sscanf!("1,٣", "{Point}").unwrap_err();
+    if cfg!(feature = "lite") {
+        // `\d` only matches ASCII digits in regex-lite
+        assert!(matches!(err, Error::MatchFailed));
+    } else {
+        assert!(matches!(err, Error::ParsingFailed(_)));
+    }
+
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(regex_options(size_limit = 1 << 20))]
+    #[sscanf(transparent)]
+    struct Id(u32);
+    assert_eq!(Id::from_str("42").unwrap(), Id(42));
+}
@@ -0,0 +1,15 @@
+#[derive(sscanf::ScanfRadix)]
+struct TwoFields(u8, u8);
+
+#[derive(sscanf::ScanfRadix)]
+struct NoFields;
+
+#[derive(sscanf::ScanfRadix)]
+enum Enum {
+    A(u8),
+}
+
+#[derive(sscanf::ScanfRadix)]
+struct NoRadix(String);
+
+fn main() {}
@@ -5,4 +5,8 @@ fn main() {
     sscanf::sscanf!("5", 'x');
     sscanf::sscanf!("5", usize);
     sscanf::sscanf_get_regex!();
+    sscanf::sscanf_with!();
+    sscanf::sscanf_with!(&mut buffer);
+    sscanf::sscanf_with!(&mut buffer,);
+    sscanf::sscanf_with!(&mut buffer, "5");
 }
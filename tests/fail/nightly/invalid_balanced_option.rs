@@ -0,0 +1,6 @@
+fn main() {
+    sscanf::sscanf!("", "{str:(),quotes}");
+    sscanf::sscanf!("", "{str:[]x}");
+    sscanf::sscanf!("", "{u32:()}");
+    sscanf::sscanf!("", "{char:<>,quoted}");
+}
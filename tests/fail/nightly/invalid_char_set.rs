@@ -0,0 +1,9 @@
+fn main() {
+    sscanf::sscanf!("", "{str:[a-z}");
+    sscanf::sscanf!("", "{str:[a-z]x}");
+    sscanf::sscanf!("", "{str:[a-z][0-9]}");
+    sscanf::sscanf!("", "{str:[z-a]}");
+    sscanf::sscanf!("", "{u32:[0-9]}");
+    sscanf::sscanf!("", "{char:ident}");
+    sscanf::sscanf!("", "{u8:alpha}");
+}
@@ -9,5 +9,4 @@ fn main() {
     sscanf::sscanf!("", "{:/\\/", str);
 
     sscanf::sscanf!("", "{:/ab(ab/}", str);
-    sscanf::sscanf!("", "{:/ab()ab/}", str);
 }
@@ -0,0 +1,27 @@
+struct NoEq;
+impl sscanf::RegexRepresentation for NoEq {
+    const REGEX: &'static str = "x";
+}
+impl std::str::FromStr for NoEq {
+    type Err = std::convert::Infallible;
+    fn from_str(_: &str) -> Result<Self, Self::Err> {
+        Ok(NoEq)
+    }
+}
+
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{a}-{=b}-{b}-{=c}-{=a.len()}")]
+struct Derived {
+    a: u8,
+    b: u8,
+    #[sscanf(default)]
+    c: u8,
+}
+
+fn main() {
+    sscanf::sscanf!("", "{u8}{=99999999999999999999999}");
+    sscanf::sscanf!("", "{=1}{u8}");
+    sscanf::sscanf!("", "{u8}{=0:x}");
+    sscanf::sscanf!("", "{usize}{str:take=0}{=1}");
+    sscanf::sscanf!("", "{NoEq}{=0}");
+}
@@ -0,0 +1,11 @@
+struct NoDisplay;
+
+fn main() {
+    let x = 5;
+    sscanf::sscanf!("", "{=x +}");
+    sscanf::sscanf!("", "{=x:x}");
+    sscanf::sscanf!("", "{=x}{=0}");
+    sscanf::sscanf!("", "{=x}{str:take=0}");
+    sscanf::sscanf_get_regex!("{=x}");
+    sscanf::sscanf!("", "{=NoDisplay}");
+}
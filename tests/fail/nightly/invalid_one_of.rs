@@ -0,0 +1,5 @@
+fn main() {
+    sscanf::sscanf!("", "{u8|str:x}");
+    sscanf::sscanf!("", "{u8|u16|u32|u64|u128|usize|str}");
+    sscanf::sscanf!("", "{u8|}");
+}
@@ -0,0 +1,4 @@
+fn main() {
+    sscanf::sscanf!("", "{str:q}");
+    sscanf::sscanf!("", "{u8:q}");
+}
@@ -0,0 +1,10 @@
+struct NoOptions;
+
+fn main() {
+    sscanf::sscanf!("", "{str:trim,trim}");
+    sscanf::sscanf!("", "{str:*,empty}");
+    sscanf::sscanf!("", "{u8:*}");
+    sscanf::sscanf!("", "{char:greedy,trim}");
+    sscanf::sscanf!("", "{str:trim,foo}");
+    sscanf::sscanf!("", "{NoOptions:greedy}");
+}
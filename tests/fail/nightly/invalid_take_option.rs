@@ -0,0 +1,15 @@
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{len}:{text:take=0}")]
+struct NetString {
+    len: usize,
+    text: String,
+}
+
+fn main() {
+    sscanf::sscanf!("", "{usize}{str:take=x}");
+    sscanf::sscanf!("", "{str:take=1}{usize}");
+    sscanf::sscanf!("", "{usize}{str:take=1}");
+    sscanf::sscanf!("", "{usize}{str:take=0}{str:take=1}");
+    sscanf::sscanf!("", "{usize}{u8:take=0}");
+    sscanf::sscanf_get_regex!("{usize}{str:take=0}");
+}
@@ -0,0 +1,21 @@
+struct Date;
+impl sscanf::FromScanfWithOptions<'_> for Date {
+    type Err = std::convert::Infallible;
+    const OPTIONS: &'static [(&'static str, &'static str)] = &[("iso", r"\d+")];
+    fn from_matches_with(_: &str, src: &mut sscanf::Captures) -> Result<Self, Self::Err> {
+        src.next();
+        Ok(Date)
+    }
+}
+
+struct NoOptions;
+
+fn main() {
+    sscanf::sscanf!("", "{Date:us}");
+    sscanf::sscanf!("", "{NoOptions:iso}");
+    sscanf::sscanf!("", "{u8:iso}");
+    sscanf::sscanf!("", "{:iso}", String);
+    sscanf::sscanf!("", "{u8:iso~}");
+    sscanf::sscanf!("", "{std::primitive::u8:iso}");
+    sscanf::sscanf!("", "{:iso}", std::string::String);
+}
@@ -0,0 +1,6 @@
+struct NoRadix(u8);
+
+fn main() {
+    sscanf::sscanf!("1", "{:x}", NoRadix);
+    sscanf::sscanf!("1", "{NoRadix:#b~}");
+}
@@ -0,0 +1,34 @@
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{x}", regex_options)]
+struct NoParens {
+    x: u8,
+}
+
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{x}", regex_options(size_limit = 5))]
+#[sscanf(regex_options(unicode = false))]
+struct Twice {
+    x: u8,
+}
+
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{x}", regex_options(sizelimit = 5))]
+struct Misspelled {
+    x: u8,
+}
+
+#[derive(sscanf::FromScanf)]
+#[sscanf(format = "{x}")]
+struct OnField {
+    #[sscanf(regex_options(unicode = false))]
+    x: u8,
+}
+
+fn main() {
+    sscanf::sscanf!("5", "{u8}";);
+    sscanf::sscanf!("5", "{u8}"; unicode);
+    sscanf::sscanf!("5", "{u8}"; unicode =);
+    sscanf::sscanf!("5", "{u8}"; unicode = false, unicode = true);
+    sscanf::sscanf!("5", "{u8}"; case_insensitive = true);
+    sscanf::sscanf!("5", "{u8}"; size_limit = 5 nest_limit = 5);
+}
@@ -0,0 +1,5 @@
+fn main() {
+    sscanf::sscanf!("", "{u8:(a|b)}");
+    sscanf::sscanf!("", "{(a|b)}{=0}");
+    sscanf::sscanf!("", "{(a)}");
+}
@@ -0,0 +1,8 @@
+fn main() {
+    sscanf::sscanf!("", "{:.}", f64);
+    sscanf::sscanf!("", "{:.999}", f64);
+    sscanf::sscanf!("", "{:,,}", f64);
+    sscanf::sscanf!("", "{:.2,.3}", f64);
+    sscanf::sscanf!("", "{:,finit}", f64);
+    sscanf::sscanf!("", "{:,}", u32);
+}
@@ -5,5 +5,9 @@ fn main() {
     sscanf::sscanf!("", "{:r}", u8);
     sscanf::sscanf!("", "{:r99}", u8);
     sscanf::sscanf!("", "{:r1}", u8);
-    sscanf::sscanf!("", "{:x}", std::u8);
+    sscanf::sscanf!("", "{:o}", f64);
+    sscanf::sscanf!("", "{:r10}", f32);
+    sscanf::sscanf!("", "{:x~}", f64);
+    sscanf::sscanf!("", "{:#~}", i8);
+    sscanf::sscanf!("", "{:,~}", f64);
 }
@@ -0,0 +1,8 @@
+const NUMBER: u32 = 5;
+
+fn main() {
+    sscanf::sscanf!("", "{str:@}");
+    sscanf::sscanf!("", "{str:@5}");
+    sscanf::sscanf!("", "{str:@a b}");
+    sscanf::sscanf!("", "{str:@NUMBER}");
+}
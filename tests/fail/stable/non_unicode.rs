@@ -1,5 +1,5 @@
 fn main() {
     type Ay̆y̆y̆ = u8;
-    sscanf::sscanf!("Hi", "y̆😛y̆{Ay̆y̆y̆:😛}y̆😛y̆");
-    sscanf::sscanf!("Hi", r##"y̆😛y̆{Ay̆y̆y̆:😛}y̆😛y̆"##);
+    sscanf::sscanf!("Hi", "y̆😛y̆{Ay̆y̆y̆:#😛}y̆😛y̆");
+    sscanf::sscanf!("Hi", r##"y̆😛y̆{Ay̆y̆y̆:#😛}y̆😛y̆"##);
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use sscanf::{sscanf, sscanf_get_regex, FromScanf};
+
+#[derive(FromScanf)]
+#[sscanf(format = "#{r:x},{g:x},{b:x}")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+#[derive(FromScanf)]
+pub enum Shape {
+    #[sscanf("circle({radius})")]
+    Circle { radius: f32 },
+    #[sscanf("rect({0}x{1})")]
+    Rect(u32, u32),
+}
+
+pub fn parse_color(input: &str) -> Option<Color> {
+    sscanf!(input, "color: {Color}").ok()
+}
+
+pub fn parse_shape(input: &str) -> Option<(String, Shape)> {
+    sscanf!(input, "{String} = {Shape}").ok()
+}
+
+pub fn parse_pair(input: &str) -> Option<(i32, &str)> {
+    sscanf!(input, "{i32}: {str:[a-z]}").ok()
+}
+
+pub fn is_point(input: &str) -> bool {
+    sscanf_get_regex!("({f64}, {f64})").is_match(input)
+}
@@ -4,7 +4,7 @@ use sscanf::*;
 use std::str::FromStr;
 
 #[test]
-#[should_panic(expected = "sscanf: Cannot generate Regex")]
+#[should_panic(expected = "InvalidRegex")]
 fn invalid_regex_representation() {
     struct Test;
     impl FromStr for Test {
@@ -50,22 +50,3 @@ fn check_error_from_str_2() {
     }
     sscanf!("bobhibob", "bob{}bob", Test).unwrap();
 }
-
-#[test]
-#[should_panic(expected = r#"sscanf: Regex has 3 capture groups, but 2 were expected.
-If you use ( ) in a custom Regex, please add a '?:' at the beginning to avoid forming a capture group like this:
-    "  (  )  "  =>  "  (?:  )  "
-"#)]
-fn custom_regex_with_capture_group() {
-    struct Test(usize);
-    impl FromStr for Test {
-        type Err = <usize as FromStr>::Err;
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            s.parse().map(Test)
-        }
-    }
-    impl RegexRepresentation for Test {
-        const REGEX: &'static str = "(.*)";
-    }
-    sscanf!("5", "{Test}").unwrap();
-}
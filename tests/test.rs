@@ -152,6 +152,742 @@ fn config_numbers() {
     assert_eq!(out, sscanf!(no_prefix, "{u8:r16} {u8:r8} {u8:r2}").unwrap());
 }
 
+#[test]
+fn config_radix_types() {
+    use std::num::{NonZeroI8, NonZeroU32};
+
+    type Addr = u64;
+    let input = "0xdeadbeef 0o17 1f";
+    let parsed = sscanf!(input, "{Addr:x} {std::primitive::u8:o} {NonZeroU32:r16}");
+    let (a, b, c) = parsed.unwrap();
+    assert_eq!(a, 0xdeadbeef);
+    assert_eq!(b, 0o17);
+    assert_eq!(c.get(), 0x1f);
+
+    let err = sscanf!("0", "{NonZeroU32:x}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+    let parsed = sscanf!("-0x80 ff", "{NonZeroI8:x} {NonZeroI8:x~}").unwrap();
+    assert_eq!((parsed.0.get(), parsed.1.get()), (-128, -1));
+
+    #[derive(sscanf::ScanfRadix, Debug, PartialEq)]
+    struct Reg(u16);
+
+    #[derive(sscanf::ScanfRadix, Debug, PartialEq)]
+    struct Offset {
+        value: i32,
+    }
+
+    let input = "r1=0xbeef r2=1111 off=-0x10 off=fffffff0";
+    let parsed = sscanf!(
+        input,
+        "r1={Reg:#x} r2={Reg:b} off={Offset:x} off={Offset:x~}"
+    );
+    let (a, b, c, d) = parsed.unwrap();
+    assert_eq!(a, Reg(0xbeef));
+    assert_eq!(b, Reg(0b1111));
+    assert_eq!(c, Offset { value: -0x10 });
+    assert_eq!(d, Offset { value: -0x10 });
+
+    // number of digits still comes from the inner type
+    sscanf!("10000", "{Reg:x}").unwrap_err();
+    sscanf!("-1", "{Reg:x}").unwrap_err();
+}
+
+#[test]
+fn config_str_options() {
+    let input = "a,,b";
+    sscanf!(input, "{str},{str},{str}").unwrap_err();
+    let parsed = sscanf!(input, "{str:*},{str:empty},{str:*}").unwrap();
+    assert_eq!(parsed, ("a", "", "b"));
+    let parsed = sscanf!(",,", "{str:*},{String:*},{Cow<str>:*}").unwrap();
+    assert_eq!(parsed, ("", String::new(), std::borrow::Cow::Borrowed("")));
+
+    let input = "a.b.c";
+    let parsed = sscanf!(input, "{str}.{str}").unwrap();
+    assert_eq!(parsed, ("a", "b.c"));
+    let parsed = sscanf!(input, "{str:greedy}.{str}").unwrap();
+    assert_eq!(parsed, ("a.b", "c"));
+    let parsed = sscanf!(input, "{str:greedy,*}.{str:*}").unwrap();
+    assert_eq!(parsed, ("a.b", "c"));
+
+    let input = String::from(" name :   value ,");
+    let parsed = sscanf!(input, "{str:trim}:{String:trim},{Cow<str>:trim,*}");
+    let (a, b, c) = parsed.unwrap();
+    assert_eq!(a, "name");
+    assert_eq!(b, "value");
+    assert_eq!(c, "");
+    // the trimmed `str` still borrows from the input
+    assert_eq!(a.as_ptr(), input[1..].as_ptr());
+
+    // CSV with empty columns
+    let input = "1, , three,";
+    let parsed = sscanf!(input, "{usize},{str:trim,empty},{str:trim,empty},{str:*}").unwrap();
+    assert_eq!(parsed, (1, "", "three", ""));
+
+    // `String` can also be written with its path
+    let parsed = sscanf!(" a ", "{std::string::String:trim}").unwrap();
+    assert_eq!(parsed, "a");
+}
+
+#[test]
+fn config_quoted() {
+    use std::borrow::Cow;
+
+    let input = r#"set "Hello, \"World\"" = 'it\'s \\ \u{41}'"#;
+    let parsed = sscanf!(input, "set {String:q} = {Cow<str>:q}").unwrap();
+    assert_eq!(parsed.0, r#"Hello, "World""#);
+    assert_eq!(parsed.1, r"it's \ A");
+
+    // without escapes, the content is borrowed from the input
+    let parsed = sscanf!(r#"name: "bob""#, "name: {Cow<str>:q}").unwrap();
+    assert!(matches!(parsed, Cow::Borrowed("bob")));
+    let parsed = sscanf!(r#""""#, "{String:q}").unwrap();
+    assert_eq!(parsed, "");
+
+    // the quotes are required and have to match
+    sscanf!("bob", "{String:q}").unwrap_err();
+    sscanf!(r#""bob'"#, "{String:q}").unwrap_err();
+
+    let err = sscanf!(r#""\x41""#, "{String:q}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+}
+
+#[test]
+fn config_balanced() {
+    let input = "call f(g(x), h(1, (2)))";
+    let parsed = sscanf!(input, "call {str}({str:()})").unwrap();
+    assert_eq!(parsed, ("f", "g(x), h(1, (2))"));
+    let parsed = sscanf!("f()", "{str}({String:()})").unwrap();
+    assert_eq!(parsed, ("f", String::new()));
+
+    // the brackets have to be balanced
+    sscanf!("f(g(x)", "{str:alpha}({str:()})").unwrap_err();
+    sscanf!("f(g)x)", "{str:alpha}({str:()})").unwrap_err();
+
+    let input = "[[1, 2], [3]] <Vec<(u8, [u8; 2])>>";
+    let parsed = sscanf!(input, "[{str:[]}] <{str:<>}>").unwrap();
+    assert_eq!(parsed, ("[1, 2], [3]", "Vec<(u8, [u8; 2])>"));
+
+    // S-expression
+    let input = "(define (sq x) (* x x))";
+    let parsed = sscanf!(input, "({str} ({str:()}) {str:()})").unwrap();
+    assert_eq!(parsed, ("define", "sq x", "(* x x)"));
+
+    // with `quoted`, brackets in strings are ignored
+    let input = r#"log("a)", '(b', "\")") = 1"#;
+    sscanf!(input, "log({str:()}) = {u8}").unwrap_err();
+    let parsed = sscanf!(input, "log({str:(),quoted}) = {u8}").unwrap();
+    assert_eq!(parsed, (r#""a)", '(b', "\")""#, 1));
+
+    // there is no limit on the nesting depth
+    for depth in &[16, 17, 100] {
+        let inner = format!("{}x{}", "(".repeat(*depth), ")".repeat(*depth));
+        let input = format!("f({}, ({}))", inner, inner);
+        let parsed = sscanf!(input, "{str}({str:()})").unwrap();
+        assert_eq!(parsed, ("f", &input[2..input.len() - 1]));
+
+        let input = format!("f({}))", inner);
+        let err = sscanf!(input, "f({str:()})").unwrap_err();
+        assert!(matches!(err, sscanf::Error::MatchFailed), "{:?}", err);
+        let input = format!("f(({})", inner);
+        let err = sscanf!(input, "f({str:()})").unwrap_err();
+        assert!(matches!(err, sscanf::Error::MatchFailed), "{:?}", err);
+    }
+    let inner = format!("{}\")\"{}", "(".repeat(20), ")".repeat(20));
+    let input = format!("log({}) = 1", inner);
+    let parsed = sscanf!(input, "log({String:(),quoted}) = {u8}").unwrap();
+    assert_eq!(parsed, (inner, 1));
+
+    // the text after the brackets may contain the closing bracket, too
+    let inner = format!("{}x{}", "(".repeat(20), ")".repeat(20));
+    let input = format!("f({}) x) y", inner);
+    let parsed = sscanf!(input, "f({str:()}) {str}").unwrap();
+    assert_eq!(parsed, (inner.as_str(), "x) y"));
+
+    // like a greedy regex, the longest text that lets the rest of the format match is used
+    let parsed = sscanf!("a, b, c", "{str:()}, {str}").unwrap();
+    assert_eq!(parsed, ("a, b", "c"));
+    let parsed = sscanf!("(a), (b) = 1", "{str:()} = {u8}").unwrap();
+    assert_eq!(parsed, ("(a), (b)", 1));
+    let err = sscanf!("(a) = 300", "{str:()} = {u8}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)), "{:?}", err);
+
+    // together with other placeholders that are found by hand
+    let input = "3:(a)(b) (b)";
+    let parsed = sscanf!(input, "{usize}:{str:take=0}{str:()} {=2}").unwrap();
+    assert_eq!(parsed, (3, "(a)", "(b)"));
+    sscanf!("3:(a)(b) (c)", "{usize}:{str:take=0}{str:()} {=2}").unwrap_err();
+    let mut buffer = ScanBuffer::new();
+    let parsed = sscanf_with!(&mut buffer, "[a]<b>", "[{str:[]}]<{str:<>}>").unwrap();
+    assert_eq!(parsed, ("a", "b"));
+}
+
+#[test]
+fn config_take() {
+    // netstrings
+    let input = "5:he,lo,";
+    let parsed = sscanf!(input, "{usize}:{str:take=0},").unwrap();
+    assert_eq!(parsed, (5, "he,lo"));
+    let input = "3:abc,0:,";
+    let parsed = sscanf!(input, "{u8}:{String:take=0},{u32}:{Cow<str>:take=2},").unwrap();
+    assert_eq!(
+        parsed,
+        (3, String::from("abc"), 0, std::borrow::Cow::Borrowed(""))
+    );
+
+    // the taken text can contain anything, including the following literal
+    let input = "set key $10 {a: 1}} {} end";
+    let parsed = sscanf!(input, "set {str} ${usize} {str:take=1} end").unwrap();
+    assert_eq!(parsed, ("key", 10, "{a: 1}} {}"));
+
+    // the length is in bytes
+    let parsed = sscanf!("2:é!", "{usize}:{str:take=0}{char}").unwrap();
+    assert_eq!(parsed, (2, "é", '!'));
+    let err = sscanf!("1:é", "{usize}:{str:take=0}{str}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+
+    let err = sscanf!("9:abc,", "{usize}:{str:take=0},").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+    let err = sscanf!("2:abc,", "{usize}:{str:take=0},").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+    let err = sscanf!("-1:abc,", "{i32}:{str:take=0},").unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+}
+
+#[test]
+fn equal_placeholders() {
+    let input = "<b>bold</b>";
+    let parsed = sscanf!(input, "<{str}>{str}</{=0}>").unwrap();
+    assert_eq!(parsed, ("b", "bold"));
+    let err = sscanf!("<b>bold</i>", "<{str}>{str}</{=0}>").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+
+    // compared after the conversion, with the same options
+    let parsed = sscanf!("0x1f == 31 == 0x1F", "{u8:x} == {u8} == {=0}").unwrap();
+    assert_eq!(parsed, (31, 31));
+    let err = sscanf!("1 + 2 = 3", "{u8} + {=0} = {u8}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+    let parsed = sscanf!("1 + 1 = 2", "{u8} + {=0} = {u8}").unwrap();
+    assert_eq!(parsed, (1, 2));
+
+    // with type indices
+    let parsed = sscanf!("a-b-a-b", "{0}-{1}-{=0}-{=1}", char, String).unwrap();
+    assert_eq!(parsed, ('a', String::from("b")));
+
+    // together with take
+    let parsed = sscanf!("3:abc,3", "{usize}:{str:take=0},{=0}").unwrap();
+    assert_eq!(parsed, (3, "abc"));
+    let err = sscanf!("3:abc,4", "{usize}:{str:take=0},{=0}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+}
+
+#[test]
+fn regex_options() {
+    let parsed = sscanf!("7 + 3", "{u8} + {u8}"; size_limit = 100_000, unicode = false).unwrap();
+    assert_eq!(parsed, (7, 3));
+    sscanf!("ab", "ab"; dfa_size_limit = 1 << 20,).unwrap();
+    let parsed = sscanf!("-5", "{}", i32; nest_limit = 10).unwrap();
+    assert_eq!(parsed, -5);
+
+    // without Unicode, `\d` only matches ASCII digits, like in regex-lite
+    let err = sscanf!("٣", "{u8}").unwrap_err();
+    if cfg!(feature = "lite") {
+        assert!(matches!(err, sscanf::Error::MatchFailed));
+    } else {
+        assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+    }
+    let err = sscanf!("٣", "{u8}"; unicode = false).unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+
+    // placeholders that match any text still match non-ASCII characters
+    let parsed = sscanf!("ä 5 ٣,x", "{char} {u8} {str:[^,]},{String}"; unicode = false).unwrap();
+    assert_eq!(parsed, ('ä', 5, "٣", String::from("x")));
+    let regex = sscanf_get_regex!("{u8}:{str}"; unicode = false);
+    assert!(regex.is_match("1:ä"));
+    assert!(!regex.is_match("١:ä"));
+
+    // every stage of a format with `take`
+    const LIMIT: usize = 1_000_000;
+    let parsed = sscanf!("2:ab!", "{usize}:{str:take=0}{char}"; size_limit = LIMIT).unwrap();
+    assert_eq!(parsed, (2, "ab", '!'));
+
+    let mut buffer = ScanBuffer::new();
+    let parsed = sscanf_with!(&mut buffer, "1 2", "{u8} {u8}"; unicode = false).unwrap();
+    assert_eq!(parsed, (1, 2));
+
+    let regex = sscanf_get_regex!("{u8}"; unicode = false);
+    assert!(regex.is_match("12"));
+    assert!(!regex.is_match("١٢"));
+
+    // a regex that exceeds the limit is reported as an error
+    let err = sscanf!("5", "{u8}{str}"; size_limit = 10).unwrap_err();
+    assert!(matches!(
+        err,
+        sscanf::Error::InvalidRegex {
+            type_name: "{u8}{str}",
+            ..
+        }
+    ));
+
+    #[derive(FromScanf, Debug)]
+    #[allow(dead_code)]
+    #[sscanf(format = "{a}-{b}", regex_options(size_limit = 10))]
+    struct Test {
+        a: u8,
+        b: String,
+    }
+    let err = Test::from_str("1-x").unwrap_err();
+    assert!(matches!(err, sscanf::Error::InvalidRegex { .. }));
+}
+
+#[test]
+#[cfg(feature = "lite")]
+fn lite() {
+    // `\d` only matches ASCII digits in regex-lite
+    let err = sscanf!("٣", "{u8}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+    let parsed = sscanf!("5 ünïcødé", "{u8} {str:[a-zäöüïø]}{char}").unwrap();
+    assert_eq!(parsed, (5, "ünïcød", 'é'));
+
+    // formats with something that regex-lite doesn't support are built with regex
+    let parsed = sscanf!("٣ é", r"{str:/\p{N}/} {char:[\pL]}").unwrap();
+    assert_eq!(parsed, ("٣", 'é'));
+    let err = sscanf!("٣", "{u8}"; dfa_size_limit = 1 << 20).unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+
+    let mut buffer = ScanBuffer::new();
+    for _ in 0..2 {
+        let parsed = sscanf_with!(&mut buffer, "1-f", "{u8}-{u8:x}").unwrap();
+        assert_eq!(parsed, (1, 15));
+    }
+
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{x},{y}")]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+    assert_eq!(Point::from_str("1,2").unwrap(), Point { x: 1, y: 2 });
+
+    // the type of the regex doesn't depend on the feature
+    let regex: &sscanf::regex::Regex = sscanf_get_regex!("{u8}");
+    assert!(regex.is_match("12"));
+}
+
+#[test]
+#[cfg(feature = "fancy")]
+fn fancy() {
+    // a word that is not followed by a colon
+    let parsed = sscanf!("key: value", r"{str:/\w+(?!:)/}: {str}");
+    assert!(matches!(parsed, Err(sscanf::Error::MatchFailed)));
+    let parsed = sscanf!("value key: 5", r"{str:/\w+(?!\w|:)/} {str}: {u8}").unwrap();
+    assert_eq!(parsed, ("value", "key", 5));
+
+    // groups in the custom regex don't take any captures
+    let parsed = sscanf!("ab12", r"{str:/(?<name>[a-z])+(?=\d)/}{u8}").unwrap();
+    assert_eq!(parsed, ("ab", 12));
+    let parsed = sscanf!("x [)] 3", r"{str:/(?<=\s)[)\]]+/}] {u8}");
+    assert!(parsed.is_err());
+    let parsed = sscanf!("x [)] 3", r"x [{str:/(?<=\[)[)\]]+/} {u8}").unwrap();
+    assert_eq!(parsed, (")]", 3));
+
+    let mut buffer = ScanBuffer::new();
+    let parsed = sscanf_with!(&mut buffer, "1a", "{u8}{char:/(?<=1)./}").unwrap();
+    assert_eq!(parsed, (1, 'a'));
+
+    let expected = 4;
+    let parsed = sscanf!("4 ab", "{=expected} {str:/a(?=b)/}b").unwrap();
+    assert_eq!(parsed, "a");
+
+    let regex: &sscanf::fancy_regex::Regex = sscanf_get_regex!("{str:/a(?!b)/}{char}");
+    assert!(regex.is_match("ac").unwrap());
+    assert!(!regex.is_match("ab").unwrap());
+}
+
+#[test]
+fn interpolation() {
+    let job_id = 42;
+    let input = "job 42 finished after 3s";
+    let parsed = sscanf!(input, "job {=job_id} finished after {u32}s").unwrap();
+    assert_eq!(parsed, 3);
+    let err = sscanf!(
+        "job 7 finished after 3s",
+        "job {=job_id} finished after {u32}s"
+    )
+    .unwrap_err();
+    assert!(matches!(err, sscanf::Error::MatchFailed));
+
+    // the value is escaped
+    struct Config {
+        name: &'static str,
+    }
+    let config = Config { name: "a.b" };
+    let parsed = sscanf!("a.b = 5", "{=config.name} = {u8}").unwrap();
+    assert_eq!(parsed, 5);
+    sscanf!("axb = 5", "{=config.name} = {u8}").unwrap_err();
+
+    // together with other placeholders that refer to indices
+    let parsed = sscanf!(
+        "1 x 1 y 3:abc",
+        r#"{u8} {="x"} {=0} y {usize}:{str:take=3}"#
+    )
+    .unwrap();
+    assert_eq!(parsed, (1, 3, "abc"));
+    for sep in [',', ';'] {
+        let input = format!("1{}2", sep);
+        let parsed = sscanf!(input, "{u8}{=sep}{u8}").unwrap();
+        assert_eq!(parsed, (1, 2));
+    }
+}
+
+#[test]
+fn alternatives() {
+    let parsed = sscanf!("1 file", "{u32} file{(|s)}").unwrap();
+    assert_eq!(parsed, 1);
+    let parsed = sscanf!("2 files", "{u32} file{(|s)}").unwrap();
+    assert_eq!(parsed, 2);
+    sscanf!("2 filez", "{u32} file{(|s)}").unwrap_err();
+
+    // the alternatives are literal text
+    let parsed = sscanf!("a.b 5", "{(a.b|a+b)} {u8}").unwrap();
+    assert_eq!(parsed, 5);
+    sscanf!("axb 5", "{(a.b|a+b)} {u8}").unwrap_err();
+
+    // as an option, the matched alternative is returned
+    let input = "status: fail (3 retries)";
+    let parsed = sscanf!(input, "status: {str:(ok|fail)} ({u8} {(retry|retries)})").unwrap();
+    assert_eq!(parsed, ("fail", 3));
+    let parsed = sscanf!(input, "status: {usize:(ok|fail)} ({u8} {(retry|retries)})").unwrap();
+    assert_eq!(parsed, (1, 3));
+    let parsed = sscanf!("apples", "{String:(apple|apples)}").unwrap();
+    assert_eq!(parsed, "apples");
+    let parsed = sscanf!("b", "{std::primitive::usize:(a|b)}").unwrap();
+    assert_eq!(parsed, 1);
+
+    // together with placeholders that refer to indices
+    let parsed = sscanf!("x: 1 = 1", "{(x|y)}: {u8} = {=1}").unwrap();
+    assert_eq!(parsed, 1);
+}
+
+#[test]
+fn one_of() {
+    let parsed = sscanf!("timeout=30", "timeout={u64|str}").unwrap();
+    assert_eq!(parsed, OneOf2::A(30));
+    let parsed = sscanf!("timeout=never", "timeout={u64|str}").unwrap();
+    assert_eq!(parsed, OneOf2::B("never"));
+
+    // the first matching type is used, even if it fails to parse
+    let parsed = sscanf!("1.5 x", "{u8|f32|String} {u8|char}").unwrap();
+    assert_eq!(parsed, (OneOf3::B(1.5), OneOf2::B('x')));
+    sscanf!("300", "{u8|str}").unwrap_err();
+
+    // types with multiple captures skip the captures of the other types
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "-{value}")]
+    struct Negative {
+        value: u8,
+    }
+    let parsed = sscanf!("a-5 3", "{str|Negative} {u8}").unwrap();
+    assert_eq!(parsed, (OneOf2::A("a-5"), 3));
+    let parsed = sscanf!("-5 -a 3", "{Negative|str} {Negative|str} {u8}").unwrap();
+    assert_eq!(parsed.0, OneOf2::A(Negative { value: 5 }));
+    assert_eq!(parsed.1, OneOf2::B("-a"));
+    assert_eq!(parsed.2, 3);
+
+    // the enum can be used in the arguments and with `{=N}`
+    let input = "abc 5 5";
+    let parsed = sscanf!(input, "{} {} {=1}", OneOf2<u8, Cow<str>>, sscanf::OneOf2<u8, &str>);
+    let (a, b) = parsed.unwrap();
+    assert_eq!(a, OneOf2::B(std::borrow::Cow::Borrowed("abc")));
+    assert_eq!(b, OneOf2::A(5));
+    sscanf!("abc 5 6", "{} {u8|str} {=1}", OneOf2<u8, Cow<str>>).unwrap_err();
+
+    assert_eq!(OneOf4::<u8, &str, char, bool>::C('c').to_string(), "c");
+}
+
+#[test]
+fn config_char_sets() {
+    let input = "my_var42 = hello, world;";
+    let parsed = sscanf!(
+        input,
+        "{str:[a-z0-9_]} = {String:[^,]}, {Cow<str>:[a-z]}{char:[;.]}"
+    );
+    let (a, b, c, d) = parsed.unwrap();
+    assert_eq!(a, "my_var42");
+    assert_eq!(b, "hello");
+    assert_eq!(c, "world");
+    assert_eq!(d, ';');
+
+    // sets are greedy and need at least one character
+    let parsed = sscanf!("aaab", "{str:[a]}{str}").unwrap();
+    assert_eq!(parsed, ("aaa", "b"));
+    sscanf!(",x", "{str:[^,]},{str}").unwrap_err();
+
+    // non-raw strings need the same escaping as regex options
+    let parsed = sscanf!(r#"a"b\c"#, "{str:[a-z\"\\\\]}").unwrap();
+    assert_eq!(parsed, r#"a"b\c"#);
+    let parsed = sscanf!("1.5-2", r"{str:[\d.]}-{char:[\d]}").unwrap();
+    assert_eq!(parsed, ("1.5", '2'));
+
+    let input = "Hello world42 DEADbeef _ident_1 XYZ \t\n lower";
+    let parsed = sscanf!(
+        input,
+        "{str:alpha} {str:alnum} {str:xdigit} {str:ident} {str:upper} {str:space} {str:lower}"
+    );
+    let (a, b, c, d, e, f, g) = parsed.unwrap();
+    assert_eq!(a, "Hello");
+    assert_eq!(b, "world42");
+    assert_eq!(c, "DEADbeef");
+    assert_eq!(d, "_ident_1");
+    assert_eq!(e, "XYZ");
+    assert_eq!(f, "\t\n");
+    assert_eq!(g, "lower");
+
+    let parsed = sscanf!("a1", "{char:alpha}{char:digit}").unwrap();
+    assert_eq!(parsed, ('a', '1'));
+    sscanf!("1a", "{str:ident}").unwrap_err();
+    sscanf!("ab", "{char:alpha}").unwrap_err();
+
+    let parsed = sscanf!("x", "{std::primitive::char:[a-z]}").unwrap();
+    assert_eq!(parsed, 'x');
+}
+
+#[test]
+fn config_user_options() {
+    use sscanf::FromScanfWithOptions;
+
+    #[derive(Debug, PartialEq)]
+    struct Money {
+        cents: u64,
+    }
+    impl FromScanfWithOptions<'_> for Money {
+        type Err = std::num::ParseIntError;
+        const OPTIONS: &'static [(&'static str, &'static str)] = &[
+            ("eur", r"\d+,\d{2} €"),
+            ("usd", r"\$\d+\.\d{2}"),
+            ("cents", r"\d+"),
+            ("trim", r"\d+"), // built-in options for other types are still available
+        ];
+        fn from_matches_with(option: &str, src: &mut sscanf::Captures) -> Result<Self, Self::Err> {
+            let text = src.next().unwrap().unwrap();
+            let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+            let cents = digits.parse()?;
+            assert!(option == "cents" || option == "trim" || digits.len() >= 3);
+            Ok(Money { cents })
+        }
+    }
+
+    let input = "12,50 € = $13.75 = 1375";
+    let parsed = sscanf!(input, "{Money:eur} = {Money:usd} = {:cents}", Money).unwrap();
+    assert_eq!(parsed.0, Money { cents: 1250 });
+    assert_eq!(parsed.1, Money { cents: 1375 });
+    assert_eq!(parsed.2, Money { cents: 1375 });
+
+    sscanf!("$13.75", "{Money:eur}").unwrap_err();
+    assert_eq!(sscanf!("5", "{Money:trim}").unwrap(), Money { cents: 5 });
+
+    // options can have capture groups and borrow from the input
+    struct Pair<'t>(&'t str, &'t str);
+    impl<'t> FromScanfWithOptions<'t> for Pair<'t> {
+        type Err = std::convert::Infallible;
+        const OPTIONS: &'static [(&'static str, &'static str)] =
+            &[("eq", r"(\w+)=(\w+)"), ("colon", r"(\w+): (\w+)")];
+        const NUM_CAPTURES: usize = 3;
+        fn from_matches_with(_: &str, src: &mut sscanf::Captures<'t>) -> Result<Self, Self::Err> {
+            src.next().unwrap();
+            let key = src.next().unwrap().unwrap();
+            let value = src.next().unwrap().unwrap();
+            Ok(Pair(key, value))
+        }
+    }
+    let input = String::from("a=b, c: d");
+    let (first, second) = sscanf!(input, "{Pair:eq}, {Pair:colon}").unwrap();
+    assert_eq!((first.0, first.1), ("a", "b"));
+    assert_eq!((second.0, second.1), ("c", "d"));
+
+    // options that are only built in for the types from std are passed on as written
+    struct Echo(String);
+    impl FromScanfWithOptions<'_> for Echo {
+        type Err = std::convert::Infallible;
+        const OPTIONS: &'static [(&'static str, &'static str)] = &[
+            ("greedy", "a"),
+            ("empty", "b"),
+            ("q", "c"),
+            ("alpha", "d"),
+            ("digit", "e"),
+            ("finite", "f"),
+            (",", "g"),
+            (".2", "h"),
+            ("eur,short", "i"),
+            ("iso~", "j"),
+            ("%d\\.%m", "k"),
+        ];
+        fn from_matches_with(option: &str, src: &mut sscanf::Captures) -> Result<Self, Self::Err> {
+            src.next();
+            Ok(Echo(option.to_string()))
+        }
+    }
+    let parsed = sscanf!(
+        "abcdefghijk",
+        "{Echo:greedy}{Echo:empty}{Echo:q}{Echo:alpha}{Echo:digit}{Echo:finite}{Echo:,}{Echo:.2}{Echo:eur,short}{Echo:iso~}{Echo:%d\\.%m}"
+    );
+    let (a, b, c, d, e, f, g, h, i, j, k) = parsed.unwrap();
+    let options = [a.0, b.0, c.0, d.0, e.0, f.0, g.0, h.0, i.0, j.0, k.0];
+    assert_eq!(
+        options[..6],
+        ["greedy", "empty", "q", "alpha", "digit", "finite"]
+    );
+    assert_eq!(options[6..], [",", ".2", "eur,short", "iso~", "%d\\.%m"]);
+    let parsed = sscanf!("k", r"{Echo:%d\.%m}").unwrap();
+    assert_eq!(parsed.0, r"%d\.%m");
+
+    // also works in derives
+    #[derive(sscanf::FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{price:usd} x{count}")]
+    struct Order {
+        price: Money,
+        count: u32,
+    }
+    let parsed = sscanf!("$1.99 x3", "{Order}").unwrap();
+    assert_eq!(parsed.price.cents, 199);
+    assert_eq!(parsed.count, 3);
+}
+
+#[test]
+fn config_twos_complement() {
+    let input = "ff 7f 80 ffff 0xfffffffe -1";
+    let parsed = sscanf!(input, "{i8:x~} {i8:x~} {i8:x~} {i16:x~} {i32:#x~} {i8:x}");
+    assert_eq!(parsed.unwrap(), (-1, 127, -128, -1, -2, -1));
+
+    let input = "11111110 377 7fffffffffffffff";
+    let parsed = sscanf!(input, "{i8:b~} {i8:r8~} {i64:r16~}");
+    assert_eq!(parsed.unwrap(), (-2, -1, i64::MAX));
+
+    // only as many digits as the type has bits, and no sign
+    sscanf!("1ff", "{i8:x~}").unwrap_err();
+    sscanf!("-1", "{i8:x~}").unwrap_err();
+    sscanf!("0xff", "{i8:r16~}").unwrap_err();
+
+    // octal digits can still exceed the width
+    let err = sscanf!("777", "{i8:o~}").unwrap_err();
+    assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+}
+
+#[test]
+fn config_floats() {
+    // decimal comma
+    let input = "1,5;-0,25;3,;,5";
+    let parsed = sscanf!(input, "{f64:,};{f64:,};{f32:,};{f32:,}");
+    assert_eq!(parsed.unwrap(), (1.5, -0.25, 3.0, 0.5));
+
+    let input = "3.5,2.25";
+    let parsed = sscanf!(input, "{f64},{f64}");
+    assert_eq!(parsed.unwrap(), (3.5, 2.25));
+
+    let input = "1,5e3";
+    let parsed = sscanf!(input, "{f64:,}");
+    assert_eq!(parsed.unwrap(), 1500.0);
+
+    let input = "1.5";
+    sscanf!(input, "{f64:,}").unwrap_err();
+
+    // fixed precision
+    let input = "price: 12.50";
+    let parsed = sscanf!(input, "price: {f64:.2}");
+    assert_eq!(parsed.unwrap(), 12.5);
+
+    sscanf!("price: 12.5", "price: {f64:.2}").unwrap_err();
+    sscanf!("price: 12.500", "price: {f64:.2}").unwrap_err();
+    sscanf!("price: 1.25e1", "price: {f64:.2}").unwrap_err();
+
+    let input = "12.3456.7";
+    let parsed = sscanf!(input, "{f64:.3}{f64:.1}");
+    assert_eq!(parsed.unwrap(), (12.345, 6.7));
+
+    let input = "42";
+    let parsed = sscanf!(input, "{f32:.0}");
+    assert_eq!(parsed.unwrap(), 42.0);
+    sscanf!("42.0", "{f32:.0}").unwrap_err();
+
+    // finite numbers only
+    let input = "price: nan";
+    assert!(sscanf!(input, "price: {f64}").unwrap().is_nan());
+    sscanf!(input, "price: {f64:finite}").unwrap_err();
+    sscanf!("-inf", "{f64:finite}").unwrap_err();
+    sscanf!("infinity", "{f64:finite}").unwrap_err();
+    assert_eq!(sscanf!("-1.5E2", "{f64:finite}").unwrap(), -150.0);
+
+    // combined
+    let input = "-1234,56 EUR";
+    let parsed = sscanf!(input, "{f64:finite,.2} EUR");
+    assert_eq!(parsed.unwrap(), -1234.56);
+    sscanf!("nan EUR", "{f64:,.2finite} EUR").unwrap_err();
+    assert!(sscanf!("NaN EUR", "{f64:,.2} EUR").unwrap().is_nan());
+
+    let parsed = sscanf!("1,5", "{std::primitive::f64:,}");
+    assert_eq!(parsed.unwrap(), 1.5);
+}
+
+#[test]
+fn config_hex_floats() {
+    // output of printf("%a %a", 12.0, 0.1)
+    let input = "0x1.8p+3 0x1.999999999999ap-4";
+    let parsed = sscanf!(input, "{f64:x} {f64:x}");
+    assert_eq!(parsed.unwrap(), (12.0, 0.1));
+
+    let input = "-0X1P-1 ap0 .8p1 1.p0 +0x0p0 -0x0.0p+5";
+    let parsed = sscanf!(input, "{f32:x} {f32:x} {f32:x} {f32:x} {f64:x} {f64:x}").unwrap();
+    assert_eq!(parsed.0, -0.5);
+    assert_eq!(parsed.1, 10.0);
+    assert_eq!(parsed.2, 1.0);
+    assert_eq!(parsed.3, 1.0);
+    assert_eq!(parsed.4.to_bits(), 0.0f64.to_bits());
+    assert_eq!(parsed.5.to_bits(), (-0.0f64).to_bits());
+
+    // the exponent is required
+    sscanf!("0x1.8", "{f64:x}").unwrap_err();
+    sscanf!("1.5", "{f64:x}").unwrap_err();
+
+    // prefix policies
+    sscanf!("1.8p3", "{f64:#x}").unwrap_err();
+    assert_eq!(sscanf!("0x1.8p3", "{f64:#x}").unwrap(), 12.0);
+    sscanf!("0x1.8p3", "{f64:r16}").unwrap_err();
+    assert_eq!(sscanf!("1.8p3", "{f64:r16}").unwrap(), 12.0);
+
+    // limits
+    let parsed = sscanf!("0x1.fffffffffffffp+1023", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::MAX);
+    let parsed = sscanf!("0x1.fffffep+127", "{f32:x}").unwrap();
+    assert_eq!(parsed, f32::MAX);
+    let parsed = sscanf!("0x1p-1074", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::from_bits(1));
+    let parsed = sscanf!("0x1p-149", "{f32:x}").unwrap();
+    assert_eq!(parsed, f32::from_bits(1));
+    let parsed = sscanf!("0x1p-1022", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::MIN_POSITIVE);
+    let parsed = sscanf!("0x1.fffffffffffffp-1023", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::MIN_POSITIVE); // subnormal that rounds up to the smallest normal
+    let parsed = sscanf!("0x1p+1024", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::INFINITY);
+    let parsed = sscanf!("-0x1p+128", "{f32:x}").unwrap();
+    assert_eq!(parsed, f32::NEG_INFINITY);
+
+    // rounding: ties to even, with any bits after the tie breaking it
+    let parsed = sscanf!("0x1.fffffffffffff8p+1023", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::INFINITY);
+    let parsed = sscanf!("0x1p-1075", "{f64:x}").unwrap();
+    assert_eq!(parsed, 0.0);
+    let parsed = sscanf!("0x1.0000000000001p-1075", "{f64:x}").unwrap();
+    assert_eq!(parsed, f64::from_bits(1));
+    let parsed = sscanf!("0x1.000001p0 0x1.000003p0", "{f32:x} {f32:x}").unwrap();
+    assert_eq!(parsed, (1.0, 1.0 + 2.0 * f32::EPSILON));
+    let parsed = sscanf!("0x1.0000010000000000000001p0", "{f32:x}").unwrap();
+    assert_eq!(parsed, 1.0 + f32::EPSILON);
+
+    let parsed = sscanf!("0x123456789abcdef0123p0", "{f64:x}").unwrap();
+    assert_eq!(parsed, 0x123456789abcdef0123u128 as f64);
+}
+
 #[test]
 fn tuple_struct_reorder() {
     #[derive(Debug, PartialEq, FromScanf)]
@@ -185,6 +921,210 @@ fn custom_regex() {
     assert_eq!(parsed.unwrap(), NoRegex);
 }
 
+mod patterns {
+    pub const IDENT: &str = "[a-zA-Z_][a-zA-Z0-9_]*";
+    pub const SEMVER: &str = r"\d+\.\d+\.\d+";
+}
+
+#[test]
+fn regex_constant() {
+    let input = "my_crate = 1.20.3;";
+    let parsed = sscanf!(
+        input,
+        "{str:@patterns::IDENT} = {str:@crate::patterns::SEMVER};"
+    );
+    assert_eq!(parsed.unwrap(), ("my_crate", "1.20.3"));
+    sscanf!(
+        "4you = 1.20.3;",
+        "{str:@patterns::IDENT} = {str:@patterns::SEMVER};"
+    )
+    .unwrap_err();
+
+    use patterns::IDENT;
+    let parsed = sscanf!("a b", "{String:@IDENT} {char:@IDENT}").unwrap();
+    assert_eq!(parsed, (String::from("a"), 'b'));
+
+    // works in derives and in `get_regex` as well
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{name:@patterns::IDENT}@{version}")]
+    struct Dependency {
+        name: String,
+        version: String,
+    }
+    let parsed = sscanf!("sscanf@0.4.4", "{Dependency}").unwrap();
+    assert_eq!(parsed.name, "sscanf");
+    let regex = sscanf_get_regex!("{str:@patterns::SEMVER}");
+    assert_eq!(regex.as_str(), r"^(\d+\.\d+\.\d+)$");
+}
+
+#[test]
+fn capture_groups_in_regex() {
+    // custom regexes are made non-capturing
+    let parsed = sscanf!("abab-5", r"{str:/(ab)+/}-{u8:/(\d)/}").unwrap();
+    assert_eq!(parsed, ("abab", 5));
+    let parsed = sscanf!("x=(1)", r"{str:/(?P<name>[a-z])/}={str:/[(]\d[)]?/}").unwrap();
+    assert_eq!(parsed, ("x", "(1)"));
+
+    // groups in the regex of a type are skipped
+    #[derive(Debug, PartialEq)]
+    struct Version(String);
+    impl FromStr for Version {
+        type Err = std::convert::Infallible;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(Version(s.to_string()))
+        }
+    }
+    impl RegexRepresentation for Version {
+        const REGEX: &'static str = r"(\d+)\.(\d+)(\.(\d+))?";
+    }
+    let parsed = sscanf!("1.2 -> 1.2.3 5", "{Version} -> {Version} {u8}").unwrap();
+    assert_eq!(parsed.0, Version(String::from("1.2")));
+    assert_eq!(parsed.1, Version(String::from("1.2.3")));
+    assert_eq!(parsed.2, 5);
+
+    #[derive(FromScanf, Debug, PartialEq)]
+    #[sscanf(format = "{name}@{version}")]
+    struct Dependency {
+        name: String,
+        version: Version,
+    }
+    let parsed = sscanf!("sscanf@0.4 x", "{Dependency} {Version|str}").unwrap();
+    assert_eq!(parsed.0.version, Version(String::from("0.4")));
+    assert_eq!(parsed.1, OneOf2::B("x"));
+
+    // as well as groups in regex constants
+    const NUMBER: &str = r"(\d+)";
+    let parsed = sscanf!("12 34", "{u8:@NUMBER} {str:@NUMBER}").unwrap();
+    assert_eq!(parsed, (12, "34"));
+}
+
+#[test]
+fn direct_parser() {
+    // these formats are matched without a regex, while sscanf_unescaped always uses the regex
+    let inputs = [
+        "1,2 -> x",
+        "+1,02 -> x y",
+        "1,2 -> ",
+        "1, 2 -> x",
+        "-1,2 -> x",
+        "1,99999999999 -> x",
+        "1,9999999999 -> x",
+        "1,2 -> a\nb",
+        "1,٣ -> x",
+        "١,2 -> x",
+    ];
+    for input in &inputs {
+        let direct = sscanf!(input, "{u32},{u32} -> {str}");
+        let regex = sscanf_unescaped!(input, "{u32},{u32} -> {str}");
+        assert_eq!(
+            format!("{:?}", direct),
+            format!("{:?}", regex),
+            "{:?}",
+            input
+        );
+    }
+
+    let inputs = [
+        "a=b=-5:x",
+        "a=5:b=6:x",
+        "=5:x",
+        "a=300:x",
+        "a=+3:",
+        "a\n=3:x",
+        "a=٣:x",
+    ];
+    for input in &inputs {
+        let direct = sscanf!(input, "{str}={i8}:{String}");
+        let regex = sscanf_unescaped!(input, "{str}={i8}:{String}");
+        assert_eq!(
+            format!("{:?}", direct),
+            format!("{:?}", regex),
+            "{:?}",
+            input
+        );
+    }
+
+    for input in &["1€€€2", "1€a€b€2", "1€a€b", "12€a€٣"] {
+        let direct = sscanf!(input, "{u8}€{str}€{u16}");
+        let regex = sscanf_unescaped!(input, "{u8}€{str}€{u16}");
+        assert_eq!(
+            format!("{:?}", direct),
+            format!("{:?}", regex),
+            "{:?}",
+            input
+        );
+    }
+
+    for input in &["-170141183460469231731687303715884105728", "", "+"] {
+        let direct = sscanf!(input, "{i128}");
+        let regex = sscanf_unescaped!(input, "{i128}");
+        assert_eq!(
+            format!("{:?}", direct),
+            format!("{:?}", regex),
+            "{:?}",
+            input
+        );
+    }
+
+    let inputs = [
+        "18446744073709551615:x",
+        "99999999999999999999:x",
+        "111111111111111111111:x",
+    ];
+    for input in &inputs {
+        let direct = sscanf!(input, "{std::primitive::usize}:{std::string::String}");
+        let regex = sscanf_unescaped!(input, "{std::primitive::usize}:{std::string::String}");
+        assert_eq!(
+            format!("{:?}", direct),
+            format!("{:?}", regex),
+            "{:?}",
+            input
+        );
+    }
+
+    let parsed = sscanf!("10,20 -> name", "{u32},{u32} -> {str}").unwrap();
+    assert_eq!(parsed, (10, 20, "name"));
+    let parsed = sscanf!("x: 5", "{Cow<str>}: {usize}").unwrap();
+    assert_eq!(parsed, (std::borrow::Cow::Borrowed("x"), 5));
+}
+
+#[test]
+fn scan_buffer() {
+    let mut buffer = ScanBuffer::new();
+    for _ in 0..3 {
+        let parsed = sscanf_with!(&mut buffer, "a=1.5 b=x", "a={f32} b={char}").unwrap();
+        assert_eq!(parsed, (1.5, 'x'));
+        let err = sscanf_with!(&mut buffer, "a=1.5 b=xy", "a={f32} b={char}").unwrap_err();
+        assert!(matches!(err, sscanf::Error::MatchFailed));
+        let err = sscanf_with!(&mut buffer, "a=1.5 b=x", "a={f32} b={u8}").unwrap_err();
+        assert!(matches!(err, sscanf::Error::MatchFailed));
+
+        // a failed conversion still puts the memory back
+        let err = sscanf_with!(&mut buffer, "a300", "{char}{u8}").unwrap_err();
+        assert!(matches!(err, sscanf::Error::ParsingFailed(_)));
+
+        // formats that don't need the regex
+        let parsed = sscanf_with!(&mut buffer, "7,8", "{u32},{u32}").unwrap();
+        assert_eq!(parsed, (7, 8));
+        let input = String::from("name: Bob");
+        let parsed = sscanf_with!(&mut buffer, input, "name: {String}").unwrap();
+        assert_eq!(parsed, "Bob");
+
+        // every stage of a format with `take`
+        let parsed = sscanf_with!(&mut buffer, "3:a,b,c!", "{usize}:{str:take=0},{str}!").unwrap();
+        assert_eq!(parsed, (3, "a,b", "c"));
+
+        // regexes with interpolation are built on every call
+        let id = 5;
+        let parsed = sscanf_with!(&mut buffer, "5: 1.0", "{=id}: {f64}").unwrap();
+        assert_eq!(parsed, 1.0);
+    }
+
+    let a = sscanf!("x(0xff)", "x({u8:x})");
+    let b = sscanf_with!(&mut buffer, "x(0xff)", "x({u8:x})");
+    assert_eq!(format!("{:?}", a), format!("{:?}", b));
+}
+
 #[test]
 fn derived_from_str() {
     #[derive(Debug, PartialEq, FromScanf)]
@@ -229,6 +1169,7 @@ fn string_lifetime() {
     }
     process_with_borrow("hi");
 
+    #[allow(clippy::needless_lifetimes)] // the explicit lifetimes are what is being tested
     fn process_with_lifetime<'a, 'b>(_a: &'a str, b: &'b str) -> &'b str {
         sscanf!(b, "{&str}").unwrap()
     }
@@ -250,6 +1191,121 @@ fn error_lifetime() {
     foo().unwrap();
 }
 
+#[test]
+#[cfg_attr(
+    feature = "panic_on_invalid_captures",
+    should_panic(expected = "sscanf: Invalid capture groups for {Verbose}")
+)]
+fn miscounted_capture_groups() {
+    // the parentheses in the comment of a verbose regex are counted as a capture group
+    const VERBOSE: &str = "(?x) a+ # (a comment)\n";
+    assert_eq!(sscanf::capture_groups(VERBOSE), 1);
+
+    #[derive(Debug)]
+    struct Verbose;
+    impl RegexRepresentation for Verbose {
+        const REGEX: &'static str = VERBOSE;
+    }
+    impl FromStr for Verbose {
+        type Err = std::convert::Infallible;
+        fn from_str(_: &str) -> Result<Self, Self::Err> {
+            Ok(Verbose)
+        }
+    }
+    let err = sscanf!("aa", "{Verbose}").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidRegexRepresentation {
+            type_name: "{Verbose}",
+            expected: 3,
+            found: 2,
+        }
+    ));
+    let err = sscanf!("aa", "{str:@VERBOSE}").unwrap_err();
+    assert!(matches!(err, Error::InvalidRegexRepresentation { .. }));
+}
+
+#[test]
+#[cfg_attr(
+    feature = "panic_on_invalid_captures",
+    should_panic(expected = "sscanf: Invalid capture groups for a-{TooFew}")
+)]
+fn invalid_regex_representation() {
+    // the regex has 2 capture groups instead of 3
+    #[derive(Debug)]
+    struct TooFew;
+    impl RegexRepresentation for TooFew {
+        const REGEX: &'static str = r"(\w)";
+    }
+    impl FromScanf<'_> for TooFew {
+        type Err = std::convert::Infallible;
+        const NUM_CAPTURES: usize = 3;
+        fn from_matches(src: &mut Captures) -> Result<Self, Self::Err> {
+            src.nth(2);
+            Ok(TooFew)
+        }
+    }
+    let err = sscanf!("a-x", "a-{TooFew}").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidRegexRepresentation {
+            type_name: "a-{TooFew}",
+            expected: 4,
+            found: 3,
+        }
+    ));
+    // also for inputs that don't match
+    let err = sscanf!("b", "a-{TooFew}").unwrap_err();
+    assert!(matches!(err, Error::InvalidRegexRepresentation { .. }));
+    let err = TooFew::from_str("x").unwrap_err();
+    assert!(matches!(err, Error::InvalidRegexRepresentation { .. }));
+
+    // from_matches takes fewer captures than NUM_CAPTURES
+    #[derive(Debug)]
+    struct TakesOne;
+    impl RegexRepresentation for TakesOne {
+        const REGEX: &'static str = r"(\w)";
+    }
+    impl FromScanf<'_> for TakesOne {
+        type Err = std::convert::Infallible;
+        const NUM_CAPTURES: usize = 2;
+        fn from_matches(src: &mut Captures) -> Result<Self, Self::Err> {
+            src.next();
+            Ok(TakesOne)
+        }
+    }
+    #[derive(FromScanf, Debug)]
+    #[sscanf(format = "<{inner}>")]
+    #[allow(dead_code)]
+    struct Wrapper {
+        inner: TakesOne,
+    }
+    if cfg!(debug_assertions) {
+        let err = sscanf!("x", "{TakesOne}").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidRegexRepresentation {
+                type_name: "TakesOne",
+                expected: 2,
+                found: 1,
+            }
+        ));
+        let err = sscanf!("<x>", "{Wrapper}").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidRegexRepresentation {
+                type_name: "TakesOne",
+                ..
+            }
+        ));
+        let err = Wrapper::from_str("<x>").unwrap_err();
+        assert!(matches!(err, Error::InvalidRegexRepresentation { .. }));
+    }
+    let err = sscanf!("x 5", "{TakesOne} {u8}").unwrap_err();
+    assert!(matches!(err, Error::InvalidRegexRepresentation { .. }));
+    assert!(err.to_string().contains("NUM_CAPTURES"));
+}
+
 #[test]
 #[ignore]
 fn error_message_tests() {